@@ -1,5 +1,6 @@
 use std::f32;
 use std::time::Instant;
+use std::sync::Arc;
 use rand::random;
 use indicatif::{ProgressBar, ProgressStyle};
 
@@ -9,26 +10,29 @@ use raytracer::core::ray::*;
 use raytracer::core::hitable::*;
 use raytracer::core::hitable_list::*;
 use raytracer::core::material::*;
+use raytracer::core::image_texture::*;
 use raytracer::core::sphere::*;
+use raytracer::core::plane::*;
 use raytracer::core::camera::*;
 use raytracer::core::image::*;
 use raytracer::core::bvh::*;
 
-fn color(r: Ray, world: &Hitable, depth: i32, mut total_rays: &mut u32) -> Vec3 {
+fn color(r: Ray, world: &dyn Hitable, depth: i32, total_rays: &mut u32) -> Vec3 {
     *total_rays += 1;
 
     let maybe_hit = world.hit(r, 0.001, f32::MAX);
 
     match maybe_hit {
         Some(hit) => {
+            let emitted = hit.material.emitted(&hit);
             if depth < 50 {
                 match hit.material.scatter(&r, &hit) {
-                    Some((attenuation, scattered)) => attenuation * color(scattered, world, depth + 1, &mut total_rays),
-                    None => Vec3::zero()
+                    Some((attenuation, scattered)) => emitted + attenuation * color(scattered, world, depth + 1, total_rays),
+                    None => emitted
                 }
             }
             else {
-                Vec3::zero()
+                emitted
             }
         },
         None => {
@@ -46,9 +50,14 @@ fn main() {
 
     let mut world = HitableList::new();
 
-    // Ground plane
-    let sphere = Sphere::new(Vec3::new(0.0, -1000.0, 0.0), 1000.0, Material::lambertian(Vec3::new(0.5, 0.5, 0.5)));
-    world.list.push(Box::new(sphere));
+    // Ground plane, checkered by an image texture repeating every unit. The camera's ray
+    // differentials pick the mip level, which keeps the checks from aliasing near the horizon.
+    let checks = (0..256 * 256)
+        .map(|i| if ((i % 256) / 128 + (i / 256) / 128) % 2 == 0 { Vec3::new(0.2, 0.3, 0.1) } else { Vec3::new(0.9, 0.9, 0.9) })
+        .collect();
+    let ground = ImageTexture::new(256, 256, checks);
+    let plane = Plane::new(Vec3::zero(), Vec3::unit_y(), Material::lambertian_texture(Arc::new(ground)));
+    world.list.push(Box::new(plane));
 
     // Central spheres
     let sphere = Sphere::new(Vec3::new(0.0, 1.0, 0.0), 1.0, Material::dielectric(1.5));
@@ -99,7 +108,8 @@ fn main() {
     let up = Vec3::unit_y();
     let dist_to_focus = (pos - target).length();
     let aperture: f32 = 0.1;
-    let cam = Camera::look_at_temporal(pos, target, up, fovy, aspect, aperture, dist_to_focus, 0.0, 1.0);
+    let cam = Camera::look_at_temporal(pos, target, up, fovy, aspect, aperture, dist_to_focus, 0.0, 1.0)
+        .with_resolution(nx as u32, ny as u32);
 
     // Output image
     let mut image = Image::new(nx, ny);
@@ -135,8 +145,8 @@ fn main() {
 
     let secs: f64 = (now.elapsed().as_millis() as f64) / 1000.0;
     let primary_rays = nx * ny * ns;
-    let primary_rays_per_second: f64 = (primary_rays as f64) / (secs as f64);
-    let total_rays_per_second: f64 = (total_rays as f64) / (secs as f64);
+    let primary_rays_per_second: f64 = (primary_rays as f64) / secs;
+    let total_rays_per_second: f64 = (total_rays as f64) / secs;
 
     println!("Ray cast time: {}", secs);
     println!("  Image size: {} x {} ({} samples per pixel)", nx, ny, ns);
@@ -13,21 +13,22 @@ use raytracer::core::sphere::*;
 use raytracer::core::camera::*;
 use raytracer::core::image::*;
 
-fn color(r: Ray, world: &Hitable, depth: i32, mut total_rays: &mut u32) -> Vec3 {
+fn color(r: Ray, world: &dyn Hitable, depth: i32, total_rays: &mut u32) -> Vec3 {
     *total_rays += 1;
 
     let maybe_hit = world.hit(r, 0.001, f32::MAX);
 
     match maybe_hit {
         Some(hit) => {
+            let emitted = hit.material.emitted(&hit);
             if depth < 50 {
                 match hit.material.scatter(&r, &hit) {
-                    Some((attenuation, scattered)) => attenuation * color(scattered, world, depth + 1, &mut total_rays),
-                    None => Vec3::zero()
+                    Some((attenuation, scattered)) => emitted + attenuation * color(scattered, world, depth + 1, total_rays),
+                    None => emitted
                 }
             }
             else {
-                Vec3::zero()
+                emitted
             }
         },
         None => {
@@ -124,8 +125,8 @@ fn main() {
 
     let secs: f64 = (now.elapsed().as_millis() as f64) / 1000.0;
     let primary_rays = nx * ny * ns;
-    let primary_rays_per_second: f64 = (primary_rays as f64) / (secs as f64);
-    let total_rays_per_second: f64 = (total_rays as f64) / (secs as f64);
+    let primary_rays_per_second: f64 = (primary_rays as f64) / secs;
+    let total_rays_per_second: f64 = (total_rays as f64) / secs;
 
     println!("Ray cast time: {}", secs);
     println!("  Image size: {} x {} ({} samples per pixel)", nx, ny, ns);
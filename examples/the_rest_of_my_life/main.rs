@@ -1,5 +1,125 @@
+use std::f32;
+use std::time::Instant;
+use rand::random;
+use indicatif::{ProgressBar, ProgressStyle};
+
 extern crate raytracer;
+use raytracer::core::vec3::*;
+use raytracer::core::ray::*;
+use raytracer::core::hitable::*;
+use raytracer::core::hitable_list::*;
+use raytracer::core::material::*;
+use raytracer::core::rect::*;
+use raytracer::core::cuboid::*;
+use raytracer::core::flip_normals::*;
+use raytracer::core::rotate::*;
+use raytracer::core::translate::*;
+use raytracer::core::camera::*;
+use raytracer::core::image::*;
+use raytracer::core::bvh::*;
+
+// No sky: everything visible is lit by the emissive materials in the scene
+fn color(r: Ray, world: &dyn Hitable, depth: i32, total_rays: &mut u32) -> Vec3 {
+    *total_rays += 1;
+
+    match world.hit(r, 0.001, f32::MAX) {
+        Some(hit) => {
+            let emitted = hit.material.emitted(&hit);
+            if depth < 50 {
+                match hit.material.scatter(&r, &hit) {
+                    Some((attenuation, scattered)) => emitted + attenuation * color(scattered, world, depth + 1, total_rays),
+                    None => emitted
+                }
+            }
+            else {
+                emitted
+            }
+        },
+        None => Vec3::zero()
+    }
+}
+
+fn main() {
+    let nx = 500;
+    let ny = 500;
+    let ns = 256;
+
+    let mut world = HitableList::new();
+
+    let red = Material::lambertian(Vec3::new(0.65, 0.05, 0.05));
+    let white = Material::lambertian(Vec3::new(0.73, 0.73, 0.73));
+    let green = Material::lambertian(Vec3::new(0.12, 0.45, 0.15));
+    let light = Material::diffuse_light(Vec3::new(15.0, 15.0, 15.0));
+
+    // Cornell box walls, with the normals flipped to face into the box where needed
+    world.list.push(Box::new(FlipNormals::new(Box::new(YZRect::new(0.0, 555.0, 0.0, 555.0, 555.0, green)))));
+    world.list.push(Box::new(YZRect::new(0.0, 555.0, 0.0, 555.0, 0.0, red)));
+    world.list.push(Box::new(FlipNormals::new(Box::new(XZRect::new(213.0, 343.0, 227.0, 332.0, 554.0, light)))));
+    world.list.push(Box::new(FlipNormals::new(Box::new(XZRect::new(0.0, 555.0, 0.0, 555.0, 555.0, white.clone())))));
+    world.list.push(Box::new(XZRect::new(0.0, 555.0, 0.0, 555.0, 0.0, white.clone())));
+    world.list.push(Box::new(FlipNormals::new(Box::new(XYRect::new(0.0, 555.0, 0.0, 555.0, 555.0, white.clone())))));
+
+    // The two blocks
+    let short_block = Cuboid::new(Vec3::zero(), Vec3::new(165.0, 165.0, 165.0), white.clone());
+    world.list.push(Box::new(Translate::new(Box::new(RotateY::new(Box::new(short_block), -18.0)), Vec3::new(130.0, 0.0, 65.0))));
+    let tall_block = Cuboid::new(Vec3::zero(), Vec3::new(165.0, 330.0, 165.0), white);
+    world.list.push(Box::new(Translate::new(Box::new(RotateY::new(Box::new(tall_block), 15.0)), Vec3::new(265.0, 0.0, 295.0))));
+
+    println!("Begin build BVH");
+    let world_bvh = BVHNode::new(&mut world.list, 0.0, 1.0);
+    println!("End build BVH");
+
+    // Camera setup
+    let fovy: f32 = 40.0;
+    let aspect = (nx as f32) / (ny as f32);
+    let pos = Vec3::new(278.0, 278.0, -800.0);
+    let target = Vec3::new(278.0, 278.0, 0.0);
+    let up = Vec3::unit_y();
+    let dist_to_focus = 10.0;
+    let aperture: f32 = 0.0;
+    let cam = Camera::look_at(pos, target, up, fovy, aspect, aperture, dist_to_focus);
+
+    // Output image
+    let mut image = Image::new(nx, ny);
+
+    let bar = ProgressBar::new(ny as u64);
+    bar.set_style(ProgressStyle::default_bar()
+        .template("[{elapsed_precise}] {bar:60.white/yellow} {pos:>7}/{len:7} {msg}")
+        .progress_chars("##-"));
+
+    let mut total_rays = 0;
+    let now = Instant::now();
+
+    for j in (0..ny).rev() {
+        for i in 0..nx {
+            let mut col = Vec3::zero();
+
+            for _s in 0..ns {
+                let u = ((i as f32) + random::<f32>()) / (nx as f32);
+                let v = ((j as f32) + random::<f32>()) / (ny as f32);
+                let r = cam.get_ray(u, v);
+
+                col += color(r, &world_bvh, 0, &mut total_rays);
+            }
+
+            col /= ns as f32;
+            col = Vec3::new(f32::sqrt(col.r()), f32::sqrt(col.g()), f32::sqrt(col.b()));
+
+            image.set_pixel(i, j, col);
+        }
+        bar.inc(1);
+    }
+    bar.finish();
+
+    let secs: f64 = (now.elapsed().as_millis() as f64) / 1000.0;
+    let primary_rays = nx * ny * ns;
+    let primary_rays_per_second: f64 = (primary_rays as f64) / secs;
+    let total_rays_per_second: f64 = (total_rays as f64) / secs;
+
+    println!("Ray cast time: {}", secs);
+    println!("  Image size: {} x {} ({} samples per pixel)", nx, ny, ns);
+    println!("  Primary rays per second: {}, primary rays: {}", primary_rays_per_second, primary_rays);
+    println!("  Total rays per second: {}, total rays: {}", total_rays_per_second, total_rays);
 
-pub fn main() {
-    println!("Hello, The Rest of my Life!");
+    image.save_as("image.ppm").expect("Couldn't write image file");
 }
\ No newline at end of file
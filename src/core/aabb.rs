@@ -37,6 +37,39 @@ impl AABB {
         }
         true
     }
+
+    // Like hit(), but returns the parametric range of the ray that lies inside the box
+    pub fn intersect(&self, r: Ray, t_min: f32, t_max: f32) -> Option<(f32, f32)> {
+        let mut t_min = t_min;
+        let mut t_max = t_max;
+        for a in 0..3 {
+            let inv_d = 1.0 / r.direction()[a];
+            let mut t0 = (self.min()[a] - r.origin()[a]) * inv_d;
+            let mut t1 = (self.max()[a] - r.origin()[a]) * inv_d;
+            if inv_d < 0.0 {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+            t_min = if t0 > t_min { t0 } else { t_min };
+            t_max = if t1 < t_max { t1 } else { t_max };
+
+            if t_max <= t_min {
+                return None
+            }
+        }
+        Some((t_min, t_max))
+    }
+
+    pub fn corners(&self) -> [Vec3; 8] {
+        let mut corners = [self.min; 8];
+        for (i, corner) in corners.iter_mut().enumerate() {
+            for a in 0..3 {
+                if i & (1 << a) != 0 {
+                    corner[a] = self.max[a];
+                }
+            }
+        }
+        corners
+    }
 }
 
 fn ffmax(a: f32, b: f32) -> f32 {
@@ -0,0 +1,33 @@
+use super::ray::*;
+use super::hitable::*;
+use super::animated_transform::*;
+use super::transformed::*;
+use super::aabb::*;
+
+// Moves a hitable along a keyframed path, posing it at each ray's time for motion blur
+#[derive(Clone)]
+pub struct Animated {
+    pub hitable: Box<dyn Hitable>,
+    pub animation: AnimatedTransform,
+}
+
+impl Animated {
+    pub fn new(hitable: Box<dyn Hitable>, animation: AnimatedTransform) -> Self {
+        Animated { hitable, animation }
+    }
+}
+
+impl Hitable for Animated {
+    fn hit(&self, r: Ray, t_min: f32, t_max: f32) -> Option<HitRecord<'_>> {
+        let transform = self.animation.interpolate(r.time());
+        hit_transformed(self.hitable.as_ref(), &transform, r, t_min, t_max)
+    }
+
+    fn bounding_box(&self, t0: f32, t1: f32) -> Option<AABB> {
+        self.hitable.bounding_box(t0, t1).map(|aabb| self.animation.motion_bounds(&aabb, t0, t1))
+    }
+
+    fn clone_to_box(&self) -> Box<dyn Hitable> {
+        Box::new(self.clone())
+    }
+}
\ No newline at end of file
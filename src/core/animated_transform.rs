@@ -0,0 +1,116 @@
+use super::vec3::*;
+use super::quaternion::*;
+use super::transform::*;
+use super::aabb::*;
+use std::f32;
+
+// Largest rotation, in radians, between the poses sampled when bounding a motion
+const MAX_BOUNDS_ROTATION_STEP: f32 = 0.08;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Keyframe {
+    pub time: f32,
+    pub translation: Vec3,
+    pub rotation: Quaternion,
+    pub scale: Vec3,
+}
+
+impl Keyframe {
+    pub fn new(time: f32, translation: Vec3, rotation: Quaternion, scale: Vec3) -> Self {
+        Keyframe { time, translation, rotation, scale }
+    }
+}
+
+// A transform that varies over time, interpolating between keyframes: translation and
+// scale linearly and rotation by slerp. Times outside the keyframes hold the nearest pose.
+#[derive(Clone, Debug)]
+pub struct AnimatedTransform {
+    keyframes: Vec<Keyframe>,
+}
+
+impl AnimatedTransform {
+    pub fn new(mut keyframes: Vec<Keyframe>) -> Self {
+        assert!(!keyframes.is_empty(), "an animated transform needs at least one keyframe");
+        keyframes.sort_by(|a, b| a.time.partial_cmp(&b.time).unwrap_or(std::cmp::Ordering::Equal));
+
+        // Interpolated scales must not pass through zero, where Transform::scale has no inverse
+        for (i, keyframe) in keyframes.iter().enumerate() {
+            for a in 0..3 {
+                let keeps_sign = i == 0 || keyframe.scale[a] * keyframes[i - 1].scale[a] > 0.0;
+                assert!(keyframe.scale[a] != 0.0 && keeps_sign, "keyframe scales must be non-zero and keep their sign");
+            }
+        }
+
+        AnimatedTransform { keyframes }
+    }
+
+    pub fn keyframes(&self) -> &[Keyframe] {
+        &self.keyframes
+    }
+
+    fn pose_at(&self, time: f32) -> (Vec3, Quaternion, Vec3) {
+        let first = self.keyframes[0];
+        let last = self.keyframes[self.keyframes.len() - 1];
+        if time <= first.time {
+            return (first.translation, first.rotation, first.scale)
+        }
+        if time >= last.time {
+            return (last.translation, last.rotation, last.scale)
+        }
+
+        let next = self.keyframes.iter().position(|k| k.time > time).unwrap_or(self.keyframes.len() - 1);
+        let (a, b) = (self.keyframes[next - 1], self.keyframes[next]);
+        let s = (time - a.time) / (b.time - a.time);
+        (
+            a.translation + s * (b.translation - a.translation),
+            a.rotation.slerp(&b.rotation, s),
+            a.scale + s * (b.scale - a.scale))
+    }
+
+    pub fn interpolate(&self, time: f32) -> Transform {
+        let (translation, rotation, scale) = self.pose_at(time);
+        Transform::translate(translation) * Transform::from_quaternion(&rotation) * Transform::scale(scale)
+    }
+
+    // A box enclosing `aabb` under every pose between t0 and t1. Translation and scale move
+    // the corners in straight lines between sampled poses, but rotation sweeps them along
+    // arcs, so the box is padded by how far an arc can bulge past its chord.
+    pub fn motion_bounds(&self, aabb: &AABB, t0: f32, t1: f32) -> AABB {
+        let corners = aabb.corners();
+
+        let mut times = vec![t0];
+        times.extend(self.keyframes.iter().map(|k| k.time).filter(|&t| t > t0 && t < t1));
+        times.push(t1);
+
+        let mut bounds: Option<AABB> = None;
+        let mut padding: f32 = 0.0;
+
+        // Keyframes split [t0, t1] into spans that each interpolate smoothly
+        for span in times.windows(2) {
+            let (ta, tb) = (span[0], span[1]);
+            let (_, rotation_a, scale_a) = self.pose_at(ta);
+            let (_, rotation_b, scale_b) = self.pose_at(tb);
+            let angle = rotation_a.angle_to(&rotation_b);
+            let steps = usize::max(1, (angle / MAX_BOUNDS_ROTATION_STEP).ceil() as usize);
+
+            for i in 0..=steps {
+                let transform = self.interpolate(ta + (tb - ta) * i as f32 / steps as f32);
+                bounds = Some(match bounds {
+                    Some(bounds) => surrounding_box(&bounds, &transform.transform_aabb(aabb)),
+                    None => transform.transform_aabb(aabb)
+                });
+            }
+
+            let half_step = 0.5 * angle / steps as f32;
+            for &corner in corners.iter() {
+                let radius = f32::max((scale_a * corner).length(), (scale_b * corner).length());
+                let stretch = ((scale_b - scale_a) * corner).length() / steps as f32;
+                let bulge = 2.0 * radius * (1.0 - f32::cos(half_step)) + 0.5 * stretch * f32::sin(half_step);
+                padding = f32::max(padding, bulge);
+            }
+        }
+
+        let bounds = bounds.unwrap_or(*aabb);
+        AABB::new(bounds.min() - padding, bounds.max() + padding)
+    }
+}
\ No newline at end of file
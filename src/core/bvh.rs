@@ -1,6 +1,6 @@
-use super::vec3::*;
 use super::ray::*;
 use super::hitable::*;
+use super::hitable_list::*;
 use super::aabb::*;
 use rand::random;
 use std::cmp::Ordering;
@@ -10,10 +10,10 @@ use std::ops::Deref;
 pub struct BVHNode {
     pub left: Box<dyn Hitable>,
     pub right: Box<dyn Hitable>,
-    pub aabb: AABB,
+    pub aabb: Option<AABB>,
 }
 
-fn box_x_compare(a: &Hitable, b: &Hitable, t0: f32, t1: f32) -> Ordering {
+fn box_x_compare(a: &dyn Hitable, b: &dyn Hitable, t0: f32, t1: f32) -> Ordering {
     let maybe_box_left = a.bounding_box(t0, t1);
     let maybe_box_right = b.bounding_box(t0, t1);
 
@@ -23,7 +23,7 @@ fn box_x_compare(a: &Hitable, b: &Hitable, t0: f32, t1: f32) -> Ordering {
     }
 }
 
-fn box_y_compare(a: &Hitable, b: &Hitable, t0: f32, t1: f32) -> Ordering {
+fn box_y_compare(a: &dyn Hitable, b: &dyn Hitable, t0: f32, t1: f32) -> Ordering {
     let maybe_box_left = a.bounding_box(t0, t1);
     let maybe_box_right = b.bounding_box(t0, t1);
 
@@ -33,7 +33,7 @@ fn box_y_compare(a: &Hitable, b: &Hitable, t0: f32, t1: f32) -> Ordering {
     }
 }
 
-fn box_z_compare(a: &Hitable, b: &Hitable, t0: f32, t1: f32) -> Ordering {
+fn box_z_compare(a: &dyn Hitable, b: &dyn Hitable, t0: f32, t1: f32) -> Ordering {
     let maybe_box_left = a.bounding_box(t0, t1);
     let maybe_box_right = b.bounding_box(t0, t1);
 
@@ -45,6 +45,25 @@ fn box_z_compare(a: &Hitable, b: &Hitable, t0: f32, t1: f32) -> Ordering {
 
 impl BVHNode {
     pub fn new(list: &mut Vec<Box<dyn Hitable>>, t0: f32, t1: f32) -> Self {
+        // Unbounded hitables (e.g. infinite planes) go in a plain list next to a BVH of the
+        // rest, so they don't stop the bounded ones from being culled
+        if list.len() > 1 && list.iter().any(|hitable| hitable.bounding_box(t0, t1).is_none()) {
+            let mut unbounded = HitableList::new();
+            let mut bounded: Vec<Box<dyn Hitable>> = vec![];
+            for hitable in list.iter() {
+                match hitable.bounding_box(t0, t1) {
+                    Some(_) => bounded.push(hitable.clone()),
+                    None => unbounded.list.push(hitable.clone())
+                };
+            }
+
+            if !bounded.is_empty() {
+                let left: Box<dyn Hitable> = Box::new(unbounded);
+                let right: Box<dyn Hitable> = Box::new(BVHNode::new(&mut bounded, t0, t1));
+                return Self { left, right, aabb: None }
+            }
+        }
+
         let axis: i32 = (3.0 * random::<f32>()) as i32;
         let n = list.len();
 
@@ -55,8 +74,8 @@ impl BVHNode {
             _ => ()
         };
 
-        let mut left: Box<dyn Hitable>;
-        let mut right: Box<dyn Hitable>;
+        let left: Box<dyn Hitable>;
+        let right: Box<dyn Hitable>;
 
         match n {
             1 => {
@@ -71,8 +90,8 @@ impl BVHNode {
                 let (left_slice, right_slice) = list.split_at(n / 2);
                 let mut list_left: Vec<Box<dyn Hitable>> = vec![];
                 let mut list_right: Vec<Box<dyn Hitable>> = vec![];
-                list_left.extend(left_slice.iter().map(|ref hitable| (**hitable).clone()));
-                list_right.extend(right_slice.iter().map(|ref hitable| (**hitable).clone()));
+                list_left.extend(left_slice.iter().map(|hitable| (*hitable).clone()));
+                list_right.extend(right_slice.iter().map(|hitable| (*hitable).clone()));
                 left = Box::new(BVHNode::new(&mut list_left, t0, t1));
                 right = Box::new(BVHNode::new(&mut list_right, t0, t1));
             }
@@ -82,15 +101,15 @@ impl BVHNode {
         let maybe_box_right = right.bounding_box(t0, t1);
 
         match (maybe_box_left, maybe_box_right) {
-            (Some(ref left_box), Some(ref right_box)) => Self { left, right, aabb: surrounding_box(left_box, right_box) },
-            _ => Self {left, right, aabb: AABB::new(Vec3::zero(), Vec3::zero()) }
+            (Some(ref left_box), Some(ref right_box)) => Self { left, right, aabb: Some(surrounding_box(left_box, right_box)) },
+            _ => Self { left, right, aabb: None }
         }
     }
 }
 
 impl Hitable for BVHNode {
-    fn hit(&self, r: Ray, t_min: f32, t_max: f32) -> Option<HitRecord> {
-        if self.aabb.hit(r, t_min, t_max) {
+    fn hit(&self, r: Ray, t_min: f32, t_max: f32) -> Option<HitRecord<'_>> {
+        if self.aabb.map_or(true, |aabb| aabb.hit(r, t_min, t_max)) {
             let maybe_hit_left = self.left.hit(r, t_min, t_max);
             let maybe_hit_right = self.right.hit(r, t_min, t_max);
 
@@ -106,7 +125,7 @@ impl Hitable for BVHNode {
     }
 
     fn bounding_box(&self, _t0: f32, _t1: f32) -> Option<AABB> {
-        Some(self.aabb)
+        self.aabb
     }
 
     fn clone_to_box(&self) -> Box<dyn Hitable> {
@@ -11,10 +11,10 @@ pub struct Camera {
     vertical: Vec3,
     u: Vec3,
     v: Vec3,
-    w: Vec3,
     lens_radius: f32,
     time0: f32,
     time1: f32,
+    pixel_size: Option<(f32, f32)>,
 }
 
 impl Camera {
@@ -34,13 +34,14 @@ impl Camera {
             vertical: 2.0 * half_height * focus_dist * v,
             u,
             v,
-            w,
             lens_radius,
             time0: 0.0,
             time1: 0.0,
+            pixel_size: None,
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn look_at_temporal(pos: Vec3, target: Vec3, up: Vec3, fovy: f32, aspect: f32, aperture: f32, focus_dist: f32, t0: f32, t1: f32) -> Self {
         let lens_radius = 0.5 * aperture;
         let theta = fovy * std::f32::consts::PI / 180.0;
@@ -57,17 +58,34 @@ impl Camera {
             vertical: 2.0 * half_height * focus_dist * v,
             u,
             v,
-            w,
             lens_radius,
             time0: t0,
             time1: t1,
+            pixel_size: None,
         }
     }
 
+    // Makes get_ray attach differentials one pixel apart, for filtering textures
+    pub fn with_resolution(mut self, nx: u32, ny: u32) -> Self {
+        self.pixel_size = Some((1.0 / nx as f32, 1.0 / ny as f32));
+        self
+    }
+
     pub fn get_ray(&self, s: f32, t: f32) -> Ray {
         let rd = self.lens_radius * random_in_unit_disk();
         let offset = self.u * rd.x() + self.v * rd.y();
         let time = self.time0 + random::<f32>() * (self.time1 - self.time0);
-        Ray::new(self.origin + offset, self.lower_left_corner + s * self.horizontal + t * self.vertical - self.origin - offset, time)
+        let origin = self.origin + offset;
+        let direction = |s: f32, t: f32| self.lower_left_corner + s * self.horizontal + t * self.vertical - origin;
+        let ray = Ray::new(origin, direction(s, t), time);
+
+        match self.pixel_size {
+            Some((ds, dt)) => ray.with_differentials(RayDifferentials {
+                rx_origin: origin,
+                rx_direction: direction(s + ds, t),
+                ry_origin: origin,
+                ry_direction: direction(s, t + dt) }),
+            None => ray
+        }
     }
 }
\ No newline at end of file
@@ -0,0 +1,79 @@
+use super::vec3::*;
+use super::ray::*;
+use super::hitable::*;
+use super::material::*;
+use super::phase_function::*;
+use super::aabb::*;
+use rand::random;
+use std::f32;
+
+// A volume of uniform density filling a closed boundary, e.g. fog or smoke. Rays passing
+// through scatter at an exponentially distributed distance, off the phase function.
+#[derive(Clone)]
+pub struct ConstantMedium {
+    pub boundary: Box<dyn Hitable>,
+    pub density: f32,
+    phase_function: Material,
+}
+
+impl ConstantMedium {
+    pub fn new(boundary: Box<dyn Hitable>, density: f32, albedo: Vec3) -> Self {
+        ConstantMedium { boundary, density, phase_function: Material::isotropic(albedo) }
+    }
+
+    // Replaces the default isotropic scattering, e.g. with PhaseFunction::henyey_greenstein
+    pub fn with_phase_function(mut self, phase: PhaseFunction) -> Self {
+        if let Material::Volumetric(ref mut volumetric) = self.phase_function {
+            volumetric.phase = phase;
+        }
+        self
+    }
+}
+
+// The part of [t_min, t_max] the ray spends inside a closed convex boundary, found from
+// where it enters and leaves the boundary wherever its origin lies
+pub fn boundary_span(boundary: &dyn Hitable, r: Ray, t_min: f32, t_max: f32) -> Option<(f32, f32)> {
+    let entry = boundary.hit(r, -f32::MAX, f32::MAX)?;
+    let exit = boundary.hit(r, entry.t + 0.0001, f32::MAX)?;
+
+    let t_enter = f32::max(entry.t, t_min);
+    let t_exit = f32::min(exit.t, t_max);
+    if t_enter < t_exit { Some((t_enter, t_exit)) } else { None }
+}
+
+impl Hitable for ConstantMedium {
+    fn hit(&self, r: Ray, t_min: f32, t_max: f32) -> Option<HitRecord<'_>> {
+        let (t_enter, t_exit) = boundary_span(self.boundary.as_ref(), r, t_min, t_max)?;
+
+        let dir_length = r.direction().length();
+        let distance_inside = (t_exit - t_enter) * dir_length;
+        let hit_distance = -(1.0 / self.density) * f32::ln(random::<f32>());
+        if hit_distance > distance_inside {
+            return None
+        }
+
+        let t = t_enter + hit_distance / dir_length;
+        let rec = HitRecord {
+            t,
+            u: 0.0,
+            v: 0.0,
+            p: r.point_at_parameter(t),
+            // Arbitrary: the phase function doesn't use it
+            normal: Vec3::unit_x(),
+            dpdu: Vec3::zero(),
+            dpdv: Vec3::zero(),
+            color: None,
+            emission: None,
+            material: &self.phase_function };
+
+        Some(rec)
+    }
+
+    fn bounding_box(&self, t0: f32, t1: f32) -> Option<AABB> {
+        self.boundary.bounding_box(t0, t1)
+    }
+
+    fn clone_to_box(&self) -> Box<dyn Hitable> {
+        Box::new(self.clone())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,126 @@
+use super::vec3::*;
+use super::ray::*;
+use super::hitable::*;
+use super::aabb::*;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CsgOperation {
+    Union,
+    Intersection,
+    Difference,
+}
+
+impl CsgOperation {
+    fn inside(self, in_left: bool, in_right: bool) -> bool {
+        match self {
+            CsgOperation::Union => in_left || in_right,
+            CsgOperation::Intersection => in_left && in_right,
+            CsgOperation::Difference => in_left && !in_right,
+        }
+    }
+}
+
+// Combines two closed hitables. Both operands must be solids whose surface normals point
+// outwards, since entering and leaving is decided from the normal at each intersection.
+#[derive(Clone)]
+pub struct Csg {
+    pub operation: CsgOperation,
+    pub left: Box<dyn Hitable>,
+    pub right: Box<dyn Hitable>,
+}
+
+impl Csg {
+    pub fn new(operation: CsgOperation, left: Box<dyn Hitable>, right: Box<dyn Hitable>) -> Self {
+        Csg { operation, left, right }
+    }
+
+    pub fn union(left: Box<dyn Hitable>, right: Box<dyn Hitable>) -> Self {
+        Csg::new(CsgOperation::Union, left, right)
+    }
+
+    pub fn intersection(left: Box<dyn Hitable>, right: Box<dyn Hitable>) -> Self {
+        Csg::new(CsgOperation::Intersection, left, right)
+    }
+
+    pub fn difference(left: Box<dyn Hitable>, right: Box<dyn Hitable>) -> Self {
+        Csg::new(CsgOperation::Difference, left, right)
+    }
+}
+
+fn is_entering(r: &Ray, rec: &HitRecord) -> bool {
+    dot(r.direction(), rec.normal) < 0.0
+}
+
+impl Hitable for Csg {
+    fn hit(&self, r: Ray, t_min: f32, t_max: f32) -> Option<HitRecord<'_>> {
+        self.hit_all(r, t_min, t_max).into_iter().next()
+    }
+
+    fn hit_all(&self, r: Ray, t_min: f32, t_max: f32) -> Vec<HitRecord<'_>> {
+        let left_hits = self.left.hit_all(r, t_min, t_max);
+        let right_hits = self.right.hit_all(r, t_min, t_max);
+
+        // If the first boundary crossed is an exit, the ray starts inside that operand
+        let mut in_left = left_hits.first().is_some_and(|rec| !is_entering(&r, rec));
+        let mut in_right = right_hits.first().is_some_and(|rec| !is_entering(&r, rec));
+        let mut inside = self.operation.inside(in_left, in_right);
+
+        let mut hits = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < left_hits.len() || j < right_hits.len() {
+            let take_left = j >= right_hits.len() || (i < left_hits.len() && left_hits[i].t <= right_hits[j].t);
+            let mut rec = if take_left {
+                in_left = is_entering(&r, &left_hits[i]);
+                i += 1;
+                left_hits[i - 1]
+            }
+            else {
+                in_right = is_entering(&r, &right_hits[j]);
+                j += 1;
+                right_hits[j - 1]
+            };
+
+            let now_inside = self.operation.inside(in_left, in_right);
+            if now_inside != inside {
+                // Surfaces of a subtracted solid bound the result from the other side
+                if !take_left && self.operation == CsgOperation::Difference {
+                    rec.normal = -1.0 * rec.normal;
+                }
+                hits.push(rec);
+                inside = now_inside;
+            }
+        }
+
+        hits
+    }
+
+    fn bounding_box(&self, t0: f32, t1: f32) -> Option<AABB> {
+        let left_box = self.left.bounding_box(t0, t1);
+        let right_box = self.right.bounding_box(t0, t1);
+
+        match self.operation {
+            CsgOperation::Union => match (left_box, right_box) {
+                (Some(ref left), Some(ref right)) => Some(surrounding_box(left, right)),
+                _ => None
+            },
+            CsgOperation::Intersection => match (left_box, right_box) {
+                (Some(ref left), Some(ref right)) => {
+                    let mut small = Vec3::zero();
+                    let mut big = Vec3::zero();
+                    for a in 0..3 {
+                        small[a] = f32::max(left.min()[a], right.min()[a]);
+                        big[a] = f32::max(small[a], f32::min(left.max()[a], right.max()[a]));
+                    }
+                    Some(AABB::new(small, big))
+                },
+                (Some(box_only), None) | (None, Some(box_only)) => Some(box_only),
+                (None, None) => None
+            },
+            CsgOperation::Difference => left_box,
+        }
+    }
+
+    fn clone_to_box(&self) -> Box<dyn Hitable> {
+        Box::new(self.clone())
+    }
+}
\ No newline at end of file
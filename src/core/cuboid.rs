@@ -0,0 +1,45 @@
+use super::vec3::*;
+use super::ray::*;
+use super::hitable::*;
+use super::hitable_list::*;
+use super::material::*;
+use super::rect::*;
+use super::flip_normals::*;
+use super::aabb::*;
+
+// An axis-aligned box. Named Cuboid so glob imports don't shadow std's Box.
+#[derive(Clone)]
+pub struct Cuboid {
+    pub pmin: Vec3,
+    pub pmax: Vec3,
+    pub faces: HitableList,
+}
+
+impl Cuboid {
+    pub fn new(p0: Vec3, p1: Vec3, mat: Material) -> Self {
+        let mut faces = HitableList::new();
+
+        faces.list.push(Box::new(XYRect::new(p0.x(), p1.x(), p0.y(), p1.y(), p1.z(), mat.clone())));
+        faces.list.push(Box::new(FlipNormals::new(Box::new(XYRect::new(p0.x(), p1.x(), p0.y(), p1.y(), p0.z(), mat.clone())))));
+        faces.list.push(Box::new(XZRect::new(p0.x(), p1.x(), p0.z(), p1.z(), p1.y(), mat.clone())));
+        faces.list.push(Box::new(FlipNormals::new(Box::new(XZRect::new(p0.x(), p1.x(), p0.z(), p1.z(), p0.y(), mat.clone())))));
+        faces.list.push(Box::new(YZRect::new(p0.y(), p1.y(), p0.z(), p1.z(), p1.x(), mat.clone())));
+        faces.list.push(Box::new(FlipNormals::new(Box::new(YZRect::new(p0.y(), p1.y(), p0.z(), p1.z(), p0.x(), mat)))));
+
+        Cuboid { pmin: p0, pmax: p1, faces }
+    }
+}
+
+impl Hitable for Cuboid {
+    fn hit(&self, r: Ray, t_min: f32, t_max: f32) -> Option<HitRecord<'_>> {
+        self.faces.hit(r, t_min, t_max)
+    }
+
+    fn bounding_box(&self, _t0: f32, _t1: f32) -> Option<AABB> {
+        Some(AABB::new(self.pmin, self.pmax))
+    }
+
+    fn clone_to_box(&self) -> Box<dyn Hitable> {
+        Box::new(self.clone())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,34 @@
+use super::ray::*;
+use super::hitable::*;
+use super::aabb::*;
+
+#[derive(Clone)]
+pub struct FlipNormals {
+    pub hitable: Box<dyn Hitable>,
+}
+
+impl FlipNormals {
+    pub fn new(hitable: Box<dyn Hitable>) -> Self {
+        FlipNormals { hitable }
+    }
+}
+
+impl Hitable for FlipNormals {
+    fn hit(&self, r: Ray, t_min: f32, t_max: f32) -> Option<HitRecord<'_>> {
+        match self.hitable.hit(r, t_min, t_max) {
+            Some(mut rec) => {
+                rec.normal = -1.0 * rec.normal;
+                Some(rec)
+            },
+            None => None
+        }
+    }
+
+    fn bounding_box(&self, t0: f32, t1: f32) -> Option<AABB> {
+        self.hitable.bounding_box(t0, t1)
+    }
+
+    fn clone_to_box(&self) -> Box<dyn Hitable> {
+        Box::new(self.clone())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,143 @@
+use super::vec3::*;
+use super::ray::*;
+use super::hitable::*;
+use super::material::*;
+use super::phase_function::*;
+use super::voxel_grid::*;
+use super::constant_medium::*;
+use super::aabb::*;
+use rand::random;
+use std::f32;
+
+// Maps a point in `bounds` to the [0, 1]^3 coordinates a voxel grid is sampled with
+fn grid_coordinates(bounds: &AABB, p: Vec3) -> Vec3 {
+    (p - bounds.min()) / (bounds.max() - bounds.min())
+}
+
+// A heterogeneous participating medium filling a closed boundary, whose density comes from
+// a voxel grid stretched over the boundary's bounding box. Collisions are found with delta
+// tracking against the grid's maximum density, which samples the true free-flight
+// distribution without ray marching bias.
+#[derive(Clone)]
+pub struct GridMedium {
+    pub boundary: Box<dyn Hitable>,
+    bounds: AABB,
+    pub density: VoxelGrid,
+    pub density_scale: f32,
+    emission: Option<(VoxelGrid, Vec3)>,
+    phase_function: Material,
+}
+
+impl GridMedium {
+    pub fn new(boundary: Box<dyn Hitable>, density: VoxelGrid, density_scale: f32, albedo: Vec3) -> Self {
+        let bounds = boundary.bounding_box(0.0, 1.0).expect("a grid medium's boundary must be bounded");
+        GridMedium {
+            boundary,
+            bounds,
+            density,
+            density_scale,
+            emission: None,
+            phase_function: Material::isotropic(albedo) }
+    }
+
+    // Adds an emission (e.g. temperature) channel, glowing with the channel value times
+    // `color`. Emission goes with absorption, so each unit of length adds density times
+    // (1 - albedo) times that radiance, and a purely scattering medium stays dark.
+    pub fn with_emission(mut self, emission: VoxelGrid, color: Vec3) -> Self {
+        self.emission = Some((emission, color));
+        self
+    }
+
+    // Replaces the default isotropic scattering, e.g. with PhaseFunction::henyey_greenstein
+    pub fn with_phase_function(mut self, phase: PhaseFunction) -> Self {
+        if let Material::Volumetric(ref mut volumetric) = self.phase_function {
+            volumetric.phase = phase;
+        }
+        self
+    }
+
+    pub fn density_at(&self, p: Vec3) -> f32 {
+        self.density_scale * self.density.sample(grid_coordinates(&self.bounds, p))
+    }
+
+    // Sampled at the same object-space point as the density, so wrapping the medium in a
+    // transform moves its glow along with it
+    pub fn emission_at(&self, p: Vec3) -> Option<Vec3> {
+        self.emission.as_ref().map(|(grid, color)| grid.sample(grid_coordinates(&self.bounds, p)) * *color)
+    }
+}
+
+impl Hitable for GridMedium {
+    fn hit(&self, r: Ray, t_min: f32, t_max: f32) -> Option<HitRecord<'_>> {
+        let majorant = self.density_scale * self.density.max_value();
+        if majorant <= 0.0 {
+            return None
+        }
+
+        let (t_enter, t_exit) = boundary_span(self.boundary.as_ref(), r, t_min, t_max)?;
+        let dir_length = r.direction().length();
+        let mut t = t_enter;
+
+        loop {
+            // Tentative collision against the majorant, accepted with probability density / majorant
+            t -= f32::ln(1.0 - random::<f32>()) / (majorant * dir_length);
+            if t >= t_exit {
+                return None
+            }
+
+            let p = r.point_at_parameter(t);
+            if random::<f32>() * majorant < self.density_at(p) {
+                let rec = HitRecord {
+                    t,
+                    u: 0.0,
+                    v: 0.0,
+                    p,
+                    normal: Vec3::unit_x(),
+                    dpdu: Vec3::zero(),
+                    dpdv: Vec3::zero(),
+                    color: None,
+                    emission: self.emission_at(p),
+                    material: &self.phase_function };
+
+                return Some(rec)
+            }
+        }
+    }
+
+    fn bounding_box(&self, _t0: f32, _t1: f32) -> Option<AABB> {
+        Some(self.bounds)
+    }
+
+    fn clone_to_box(&self) -> Box<dyn Hitable> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::cuboid::*;
+    use super::super::translate::*;
+
+    #[test]
+    fn translated_emission_follows_density() {
+        // Only the low-x half of the unit box is dense and glowing, then the box is moved
+        let half = |p: Vec3| if p.x() < 0.5 { 1.0 } else { 0.0 };
+        let boundary = Cuboid::new(Vec3::zero(), Vec3::one(), Material::isotropic(Vec3::zero()));
+        let medium = GridMedium::new(Box::new(boundary), VoxelGrid::from_fn(9, 9, 9, half), 20.0, Vec3::new(0.5, 0.5, 0.5))
+            .with_emission(VoxelGrid::from_fn(9, 9, 9, half), Vec3::one());
+        let moved = Translate::new(Box::new(medium), Vec3::new(10.0, 0.0, 0.0));
+
+        let mut hits = 0;
+        for i in 0..200 {
+            let y = (i as f32 + 0.5) / 200.0;
+            let r = Ray::new(Vec3::new(9.0, y, 0.5), Vec3::unit_x(), 0.0);
+            if let Some(rec) = moved.hit(r, 0.001, f32::MAX) {
+                hits += 1;
+                assert!(rec.p.x() < 10.5, "collision at {:?} outside the dense half", rec.p);
+                assert!(rec.material.emitted(&rec).x() > 0.0, "no emission at {:?}", rec.p);
+            }
+        }
+        assert!(hits > 150);
+    }
+}
\ No newline at end of file
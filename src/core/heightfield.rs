@@ -0,0 +1,191 @@
+use super::vec3::*;
+use super::ray::*;
+use super::hitable::*;
+use super::material::*;
+use super::triangle::*;
+use super::aabb::*;
+use std::sync::Arc;
+
+// Terrain over a regular grid of heights. Each cell is split into two triangles, but
+// they're never built explicitly: rays walk the grid cell by cell (a 2D DDA), skipping
+// cells whose height range they pass over or under.
+#[derive(Clone)]
+pub struct Heightfield {
+    heights: Arc<Vec<f32>>,
+    normals: Arc<Vec<Vec3>>,
+    cell_ranges: Arc<Vec<(f32, f32)>>,
+    nx: usize,
+    nz: usize,
+    origin: Vec3,
+    size: Vec3,
+    aabb: AABB,
+    pub material: Material,
+}
+
+impl Heightfield {
+    // `heights` holds nx * nz samples in [0, 1], x varying fastest. The field spans
+    // `size` from `origin`, with a sample of 1 reaching origin.y + size.y.
+    pub fn new(heights: Vec<f32>, nx: usize, nz: usize, origin: Vec3, size: Vec3, mat: Material) -> Self {
+        assert!(nx >= 2 && nz >= 2, "a heightfield needs at least 2x2 samples");
+        assert_eq!(heights.len(), nx * nz, "heightfield sample count doesn't match its dimensions");
+
+        let heights: Vec<f32> = heights.iter().map(|h| origin.y() + h * size.y()).collect();
+        let cell_x = size.x() / (nx - 1) as f32;
+        let cell_z = size.z() / (nz - 1) as f32;
+        let at = |i: usize, j: usize| heights[i + j * nx];
+
+        let mut normals = Vec::with_capacity(nx * nz);
+        for j in 0..nz {
+            for i in 0..nx {
+                let (i0, i1) = (i.saturating_sub(1), usize::min(i + 1, nx - 1));
+                let (j0, j1) = (j.saturating_sub(1), usize::min(j + 1, nz - 1));
+                let dhdx = (at(i1, j) - at(i0, j)) / ((i1 - i0) as f32 * cell_x);
+                let dhdz = (at(i, j1) - at(i, j0)) / ((j1 - j0) as f32 * cell_z);
+                normals.push(unit_vector(Vec3::new(-dhdx, 1.0, -dhdz)));
+            }
+        }
+
+        let mut cell_ranges = Vec::with_capacity((nx - 1) * (nz - 1));
+        for j in 0..nz - 1 {
+            for i in 0..nx - 1 {
+                let corners = [at(i, j), at(i + 1, j), at(i, j + 1), at(i + 1, j + 1)];
+                let low = corners.iter().cloned().fold(f32::MAX, f32::min);
+                let high = corners.iter().cloned().fold(-f32::MAX, f32::max);
+                cell_ranges.push((low, high));
+            }
+        }
+
+        let low = cell_ranges.iter().map(|r| r.0).fold(f32::MAX, f32::min);
+        let high = cell_ranges.iter().map(|r| r.1).fold(-f32::MAX, f32::max);
+        // Pad so that perfectly flat terrain still has a non-degenerate box
+        let aabb = AABB::new(
+            Vec3::new(origin.x(), low - 0.0001, origin.z()),
+            Vec3::new(origin.x() + size.x(), high + 0.0001, origin.z() + size.z()));
+
+        Heightfield {
+            heights: Arc::new(heights),
+            normals: Arc::new(normals),
+            cell_ranges: Arc::new(cell_ranges),
+            nx,
+            nz,
+            origin,
+            size,
+            aabb,
+            material: mat }
+    }
+
+    fn vertex(&self, i: usize, j: usize) -> Vec3 {
+        Vec3::new(
+            self.origin.x() + self.size.x() * i as f32 / (self.nx - 1) as f32,
+            self.heights[i + j * self.nx],
+            self.origin.z() + self.size.z() * j as f32 / (self.nz - 1) as f32)
+    }
+
+    fn hit_cell(&self, i: usize, j: usize, r: &Ray, t_min: f32, t_max: f32) -> Option<HitRecord<'_>> {
+        let corners = [(i, j), (i + 1, j + 1), (i + 1, j), (i, j + 1)];
+        let triangles = [[corners[0], corners[1], corners[2]], [corners[0], corners[3], corners[1]]];
+        let mut closest_so_far = t_max;
+        let mut best_hit = None;
+
+        for triangle in triangles.iter() {
+            let [c0, c1, c2] = *triangle;
+            let (p0, p1, p2) = (self.vertex(c0.0, c0.1), self.vertex(c1.0, c1.1), self.vertex(c2.0, c2.1));
+
+            if let Some((t, b1, b2)) = intersect_triangle(p0, p1, p2, r, t_min, closest_so_far) {
+                let normal_at = |c: (usize, usize)| self.normals[c.0 + c.1 * self.nx];
+                let b0 = 1.0 - b1 - b2;
+                let p = r.point_at_parameter(t);
+
+                // u and v run along x and z, so the derivatives climb the triangle's slope
+                let face_normal = cross(p1 - p0, p2 - p0);
+                let dhdx = -face_normal.x() / face_normal.y();
+                let dhdz = -face_normal.z() / face_normal.y();
+
+                closest_so_far = t;
+                best_hit = Some(HitRecord {
+                    t,
+                    u: (p.x() - self.origin.x()) / self.size.x(),
+                    v: (p.z() - self.origin.z()) / self.size.z(),
+                    p,
+                    normal: unit_vector(b0 * normal_at(c0) + b1 * normal_at(c1) + b2 * normal_at(c2)),
+                    dpdu: self.size.x() * Vec3::new(1.0, dhdx, 0.0),
+                    dpdv: self.size.z() * Vec3::new(0.0, dhdz, 1.0),
+                    color: None,
+                    emission: None,
+                    material: &self.material });
+            }
+        }
+
+        best_hit
+    }
+}
+
+impl Hitable for Heightfield {
+    fn hit(&self, r: Ray, t_min: f32, t_max: f32) -> Option<HitRecord<'_>> {
+        let (t_enter, t_exit) = self.aabb.intersect(r, t_min, t_max)?;
+
+        let cells = [self.nx - 1, self.nz - 1];
+        let cell_size = [self.size.x() / cells[0] as f32, self.size.z() / cells[1] as f32];
+        let origin = [self.origin.x(), self.origin.z()];
+        let o = [r.origin().x(), r.origin().z()];
+        let d = [r.direction().x(), r.direction().z()];
+        let start = r.point_at_parameter(t_enter);
+        let start = [start.x(), start.z()];
+
+        let mut cell = [0usize; 2];
+        let mut step = [0isize; 2];
+        let mut t_next = [f32::MAX; 2];
+        let mut t_delta = [f32::MAX; 2];
+        for a in 0..2 {
+            let index = ((start[a] - origin[a]) / cell_size[a]).floor();
+            cell[a] = (index.max(0.0) as usize).min(cells[a] - 1);
+
+            if d[a] > 0.0 {
+                step[a] = 1;
+                t_delta[a] = cell_size[a] / d[a];
+                t_next[a] = (origin[a] + (cell[a] + 1) as f32 * cell_size[a] - o[a]) / d[a];
+            }
+            else if d[a] < 0.0 {
+                step[a] = -1;
+                t_delta[a] = -cell_size[a] / d[a];
+                t_next[a] = (origin[a] + cell[a] as f32 * cell_size[a] - o[a]) / d[a];
+            }
+        }
+
+        let mut t_cell_enter = t_enter;
+        loop {
+            let t_cell_exit = f32::min(f32::min(t_next[0], t_next[1]), t_exit);
+
+            // Reject the cell if the ray stays above or below its height range
+            let (low, high) = self.cell_ranges[cell[0] + cell[1] * cells[0]];
+            let y0 = r.point_at_parameter(t_cell_enter).y();
+            let y1 = r.point_at_parameter(t_cell_exit).y();
+            if f32::min(y0, y1) <= high && f32::max(y0, y1) >= low {
+                if let Some(rec) = self.hit_cell(cell[0], cell[1], &r, t_min, t_max) {
+                    return Some(rec)
+                }
+            }
+
+            if t_cell_exit >= t_exit {
+                return None
+            }
+
+            let a = if t_next[0] < t_next[1] { 0 } else { 1 };
+            let next = cell[a] as isize + step[a];
+            if next < 0 || next >= cells[a] as isize {
+                return None
+            }
+            cell[a] = next as usize;
+            t_cell_enter = t_next[a];
+            t_next[a] += t_delta[a];
+        }
+    }
+
+    fn bounding_box(&self, _t0: f32, _t1: f32) -> Option<AABB> {
+        Some(self.aabb)
+    }
+
+    fn clone_to_box(&self) -> Box<dyn Hitable> {
+        Box::new(self.clone())
+    }
+}
\ No newline at end of file
@@ -4,31 +4,59 @@ use super::material::*;
 use super::aabb::*;
 
 #[derive(Clone, Copy, Debug)]
-pub struct HitRecord {
+pub struct HitRecord<'a> {
     pub t: f32,
+    pub u: f32,
+    pub v: f32,
     pub p: Vec3,
     pub normal: Vec3,
-    pub material: Material,
+    // Surface derivatives with respect to (u, v), for texture filtering. Zero when a
+    // hitable doesn't provide them.
+    pub dpdu: Vec3,
+    pub dpdv: Vec3,
+    // Colour interpolated from mesh vertex colours, multiplied into the albedo
+    pub color: Option<Vec3>,
+    // Radiance a medium emits at the collision, looked up where the medium was sampled
+    pub emission: Option<Vec3>,
+    pub material: &'a Material,
 }
 
-impl HitRecord {
-    pub fn new() -> Self {
+impl<'a> HitRecord<'a> {
+    pub fn new(material: &'a Material) -> Self {
         HitRecord { 
             t: 0.0, 
+            u: 0.0,
+            v: 0.0,
             p: Vec3::new(0.0, 0.0, 0.0), 
             normal: Vec3::new(0.0, 0.0, 1.0), 
-            material: Material::Lambertian(Lambertian::new(Vec3::new(0.0, 0.0, 0.0))) }
+            dpdu: Vec3::zero(),
+            dpdv: Vec3::zero(),
+            color: None,
+            emission: None,
+            material }
     }
 }
 
-impl Default for HitRecord {
-    fn default() -> Self {
-        Self::new()
-    }
-}
+// Step taken past each hit when collecting every intersection along a ray
+const HIT_ALL_EPSILON: f32 = 0.0001;
 
 pub trait Hitable {
-    fn hit(&self, r: Ray, t_min: f32, t_max: f32) -> Option<HitRecord>;
+    fn hit(&self, r: Ray, t_min: f32, t_max: f32) -> Option<HitRecord<'_>>;
+
+    // Every intersection in (t_min, t_max), nearest first. The default walks along the
+    // ray with repeated hit() calls; hitables that know their intervals can override it.
+    fn hit_all(&self, r: Ray, t_min: f32, t_max: f32) -> Vec<HitRecord<'_>> {
+        let mut hits = Vec::new();
+        let mut t = t_min;
+
+        while let Some(rec) = self.hit(r, t, t_max) {
+            t = rec.t + HIT_ALL_EPSILON;
+            hits.push(rec);
+        }
+
+        hits
+    }
+
     fn bounding_box(&self, t0: f32, t1: f32) -> Option<AABB>;
     fn clone_to_box(&self) -> Box<dyn Hitable>;
 }
@@ -42,7 +42,7 @@ impl Image {
     }
 
     pub fn save_as(&self, filename: &str) -> std::io::Result<()> {
-        let file = File::create(filename.to_string()).expect("Unable to create file");
+        let file = File::create(filename).expect("Unable to create file");
         let mut buf_writer = BufWriter::new(file);
         write!(buf_writer, "P3\n{} {}\n255\n", self.width, self.height)?;
 
@@ -50,9 +50,10 @@ impl Image {
             for i in 0..self.width {
                 let col = self.image_data[i + j * self.width];
 
-                let ir : i32 = (255.99 * col.r()) as i32;
-                let ig : i32 = (255.99 * col.g()) as i32;
-                let ib : i32 = (255.99 * col.b()) as i32;
+                // Lights and bright highlights exceed 1, which PPM can't represent
+                let ir : i32 = (255.99 * col.r().clamp(0.0, 1.0)) as i32;
+                let ig : i32 = (255.99 * col.g().clamp(0.0, 1.0)) as i32;
+                let ib : i32 = (255.99 * col.b().clamp(0.0, 1.0)) as i32;
 
                 writeln!(buf_writer, "{} {} {}", ir, ig, ib)?;
             }
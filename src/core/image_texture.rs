@@ -0,0 +1,139 @@
+use super::vec3::*;
+use super::texture::*;
+use std::sync::Arc;
+
+// How texture coordinates outside [0, 1] are brought back onto the image
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WrapMode {
+    Repeat,
+    Clamp,
+}
+
+// The inverse of the sRGB transfer curve, for texels authored as display colours
+pub fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 { c / 12.92 } else { f32::powf((c + 0.055) / 1.055, 2.4) }
+}
+
+// One level of a mip pyramid, rows from top to bottom
+#[derive(Clone, Debug)]
+struct MipLevel {
+    width: usize,
+    height: usize,
+    texels: Vec<Vec3>,
+}
+
+impl MipLevel {
+    // Box filters 2x2 blocks, clamping at the far edge of odd-sized levels
+    fn downsample(&self) -> MipLevel {
+        let width = usize::max(1, self.width / 2);
+        let height = usize::max(1, self.height / 2);
+        let at = |i: usize, j: usize| self.texels[usize::min(i, self.width - 1) + usize::min(j, self.height - 1) * self.width];
+
+        let mut texels = Vec::with_capacity(width * height);
+        for j in 0..height {
+            for i in 0..width {
+                texels.push(0.25 * (at(2 * i, 2 * j) + at(2 * i + 1, 2 * j) + at(2 * i, 2 * j + 1) + at(2 * i + 1, 2 * j + 1)));
+            }
+        }
+        MipLevel { width, height, texels }
+    }
+}
+
+// An image wrapped over a surface by its (u, v) coordinates, with v = 0 at the bottom row.
+// Texels must already be linear; load_image_texture converts from sRGB. A mip pyramid is
+// built up front so that filtered lookups cost the same however large the footprint.
+#[derive(Clone, Debug)]
+pub struct ImageTexture {
+    levels: Arc<Vec<MipLevel>>,
+    pub wrap: WrapMode,
+}
+
+impl ImageTexture {
+    // `texels` holds width * height colours in rows from top to bottom
+    pub fn new(width: usize, height: usize, texels: Vec<Vec3>) -> Self {
+        assert!(width >= 1 && height >= 1, "an image texture needs at least one texel");
+        assert_eq!(texels.len(), width * height, "texel count doesn't match the image dimensions");
+
+        let mut levels = vec![MipLevel { width, height, texels }];
+        while levels[levels.len() - 1].width > 1 || levels[levels.len() - 1].height > 1 {
+            let next = levels[levels.len() - 1].downsample();
+            levels.push(next);
+        }
+
+        ImageTexture { levels: Arc::new(levels), wrap: WrapMode::Repeat }
+    }
+
+    pub fn with_wrap(mut self, wrap: WrapMode) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    pub fn width(&self) -> usize {
+        self.levels[0].width
+    }
+
+    pub fn height(&self) -> usize {
+        self.levels[0].height
+    }
+
+    pub fn num_levels(&self) -> usize {
+        self.levels.len()
+    }
+
+    fn texel(&self, level: &MipLevel, i: i64, j: i64) -> Vec3 {
+        let fit = |n: i64, size: usize| match self.wrap {
+            WrapMode::Repeat => n.rem_euclid(size as i64) as usize,
+            WrapMode::Clamp => n.clamp(0, size as i64 - 1) as usize,
+        };
+        level.texels[fit(i, level.width) + fit(j, level.height) * level.width]
+    }
+
+    fn bilinear_at(&self, level: &MipLevel, u: f32, v: f32) -> Vec3 {
+        let x = u * level.width as f32 - 0.5;
+        let y = (1.0 - v) * level.height as f32 - 0.5;
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let (i, j) = (x0 as i64, y0 as i64);
+
+        let top = (1.0 - fx) * self.texel(level, i, j) + fx * self.texel(level, i + 1, j);
+        let bottom = (1.0 - fx) * self.texel(level, i, j + 1) + fx * self.texel(level, i + 1, j + 1);
+        (1.0 - fy) * top + fy * bottom
+    }
+
+    // Blends the four full-resolution texels whose centers surround (u, v)
+    pub fn bilinear(&self, u: f32, v: f32) -> Vec3 {
+        self.bilinear_at(&self.levels[0], u, v)
+    }
+
+    // Blends bilinear lookups in the two mip levels whose texels best match `width`, the
+    // footprint size in full-resolution texels
+    pub fn trilinear(&self, u: f32, v: f32, width: f32) -> Vec3 {
+        let level = f32::log2(f32::max(width, 1e-8));
+        let last = self.levels.len() - 1;
+        if level <= 0.0 {
+            return self.bilinear(u, v)
+        }
+        if level >= last as f32 {
+            return self.bilinear_at(&self.levels[last], u, v)
+        }
+
+        let lower = level.floor() as usize;
+        let t = level - lower as f32;
+        (1.0 - t) * self.bilinear_at(&self.levels[lower], u, v) + t * self.bilinear_at(&self.levels[lower + 1], u, v)
+    }
+}
+
+impl Texture for ImageTexture {
+    fn value(&self, u: f32, v: f32, _p: &Vec3) -> Vec3 {
+        self.bilinear(u, v)
+    }
+
+    // Isotropic: the longest of the footprint's axes picks the level, so grazing views
+    // trade some sharpness for freedom from aliasing
+    fn filtered_value(&self, u: f32, v: f32, _p: &Vec3, footprint: &TextureFootprint) -> Vec3 {
+        let (w, h) = (self.width() as f32, self.height() as f32);
+        let dx = f32::hypot(footprint.dudx * w, footprint.dvdx * h);
+        let dy = f32::hypot(footprint.dudy * w, footprint.dvdy * h);
+        self.trilinear(u, v, f32::max(dx, dy))
+    }
+}
\ No newline at end of file
@@ -0,0 +1,42 @@
+use super::ray::*;
+use super::hitable::*;
+use super::transform::*;
+use super::transformed::*;
+use super::bvh::*;
+use super::aabb::*;
+use std::sync::Arc;
+
+// A placement of shared prototype geometry. Cloning an instance never copies the
+// prototype, so thousands of instances of one heavy mesh or BVH cost a transform each.
+#[derive(Clone)]
+pub struct Instance {
+    pub prototype: Arc<dyn Hitable>,
+    pub transform: Transform,
+}
+
+impl Instance {
+    pub fn new(prototype: Arc<dyn Hitable>, transform: Transform) -> Self {
+        Instance { prototype, transform }
+    }
+}
+
+impl Hitable for Instance {
+    fn hit(&self, r: Ray, t_min: f32, t_max: f32) -> Option<HitRecord<'_>> {
+        hit_transformed(self.prototype.as_ref(), &self.transform, r, t_min, t_max)
+    }
+
+    fn bounding_box(&self, t0: f32, t1: f32) -> Option<AABB> {
+        self.prototype.bounding_box(t0, t1).map(|aabb| self.transform.transform_aabb(&aabb))
+    }
+
+    fn clone_to_box(&self) -> Box<dyn Hitable> {
+        Box::new(self.clone())
+    }
+}
+
+// Builds the top level of a two-level hierarchy: a BVH over instances whose prototypes
+// carry their own acceleration structures.
+pub fn build_instance_bvh(instances: &[Instance], t0: f32, t1: f32) -> BVHNode {
+    let mut list: Vec<Box<dyn Hitable>> = instances.iter().map(|instance| instance.clone_to_box()).collect();
+    BVHNode::new(&mut list, t0, t1)
+}
\ No newline at end of file
@@ -2,19 +2,106 @@ use super::vec3::*;
 use super::ray::*;
 use super::hitable::*;
 use super::sampling::*;
+use super::phase_function::*;
+use super::texture::*;
 use rand::random;
+use std::sync::Arc;
 
 pub trait Scatterable {
     fn scatter(&self, r_in: &Ray, rec: &HitRecord) -> Option<(Vec3, Ray)>;
+
+    // Radiance given off at the hit, added to whatever is scattered in
+    fn emitted(&self, _rec: &HitRecord) -> Vec3 {
+        Vec3::zero()
+    }
 }
 
-#[derive(Clone, Copy, Debug)]
+// Where a ray's differentials cross the plane tangent to the surface at the hit
+fn transfer_differentials(d: &RayDifferentials, rec: &HitRecord) -> Option<(Vec3, Vec3)> {
+    let plane = dot(rec.normal, rec.p);
+    let intersect = |origin: Vec3, direction: Vec3| {
+        let denom = dot(rec.normal, direction);
+        if denom.abs() < 1e-8 {
+            None
+        }
+        else {
+            Some(origin + ((plane - dot(rec.normal, origin)) / denom) * direction)
+        }
+    };
+
+    Some((intersect(d.rx_origin, d.rx_direction)?, intersect(d.ry_origin, d.ry_direction)?))
+}
+
+// Solves dp = dpdu * du + dpdv * dv in the least squares sense for each pixel offset
+fn texture_footprint(rec: &HitRecord, px: Vec3, py: Vec3) -> Option<TextureFootprint> {
+    let (a00, a01, a11) = (dot(rec.dpdu, rec.dpdu), dot(rec.dpdu, rec.dpdv), dot(rec.dpdv, rec.dpdv));
+    let det = a00 * a11 - a01 * a01;
+    if det.abs() < 1e-12 {
+        return None
+    }
+
+    let solve = |dp: Vec3| {
+        let (b0, b1) = (dot(rec.dpdu, dp), dot(rec.dpdv, dp));
+        ((a11 * b0 - a01 * b1) / det, (a00 * b1 - a01 * b0) / det)
+    };
+    let (dudx, dvdx) = solve(px - rec.p);
+    let (dudy, dvdy) = solve(py - rec.p);
+
+    Some(TextureFootprint { dudx, dvdx, dudy, dvdy })
+}
+
+// Looks up a texture at the hit, filtered over the pixel footprint when the ray carries differentials
+fn texture_value(texture: &dyn Texture, r_in: &Ray, rec: &HitRecord) -> Vec3 {
+    let footprint = r_in.differentials
+        .and_then(|d| transfer_differentials(&d, rec))
+        .and_then(|(px, py)| texture_footprint(rec, px, py));
+
+    match footprint {
+        Some(ref footprint) => texture.filtered_value(rec.u, rec.v, &rec.p, footprint),
+        None => texture.value(rec.u, rec.v, &rec.p)
+    }
+}
+
+// The albedo at the hit: the texture, tinted by any colour interpolated from the mesh vertices
+fn albedo_value(texture: &dyn Texture, r_in: &Ray, rec: &HitRecord) -> Vec3 {
+    let albedo = texture_value(texture, r_in, rec);
+    match rec.color {
+        Some(color) => color * albedo,
+        None => albedo
+    }
+}
+
+// Differentials of a mirror reflection, treating the surface as locally flat
+fn reflect_differentials(r_in: &Ray, rec: &HitRecord) -> Option<RayDifferentials> {
+    let d = r_in.differentials?;
+    let (px, py) = transfer_differentials(&d, rec)?;
+
+    Some(RayDifferentials {
+        rx_origin: px,
+        rx_direction: reflect(d.rx_direction, rec.normal),
+        ry_origin: py,
+        ry_direction: reflect(d.ry_direction, rec.normal) })
+}
+
+// Differentials of a refraction, treating the surface as locally flat
+fn refract_differentials(r_in: &Ray, rec: &HitRecord, outward_normal: Vec3, ni_over_nt: f32) -> Option<RayDifferentials> {
+    let d = r_in.differentials?;
+    let (px, py) = transfer_differentials(&d, rec)?;
+    let (mut rx_direction, mut ry_direction) = (Vec3::zero(), Vec3::zero());
+    if !refract(d.rx_direction, outward_normal, ni_over_nt, &mut rx_direction) || !refract(d.ry_direction, outward_normal, ni_over_nt, &mut ry_direction) {
+        return None
+    }
+
+    Some(RayDifferentials { rx_origin: px, rx_direction, ry_origin: py, ry_direction })
+}
+
+#[derive(Clone, Debug)]
 pub struct Lambertian {
-    pub albedo: Vec3,
+    pub albedo: Arc<dyn Texture>,
 }
 
 impl Lambertian {
-    pub fn new(albedo: Vec3) -> Self {
+    pub fn new(albedo: Arc<dyn Texture>) -> Self {
         Lambertian { albedo }
     }
 }
@@ -23,7 +110,7 @@ impl Scatterable for Lambertian {
     fn scatter(&self, r_in: &Ray, rec: &HitRecord) -> Option<(Vec3, Ray)> {
         let target = rec.p + rec.normal + random_in_unit_sphere();
                 
-        Some((self.albedo, Ray::new(rec.p, target - rec.p, r_in.time())))
+        Some((albedo_value(self.albedo.as_ref(), r_in, rec), Ray::new(rec.p, target - rec.p, r_in.time())))
     }
 }
 
@@ -51,13 +138,13 @@ fn schlick(cosine: f32, ref_idx: f32) -> f32 {
     r0 + (1.0 - r0) * f32::powf(1.0 - cosine, 5.0)
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 pub struct Metal {
-    pub albedo: Vec3,
+    pub albedo: Arc<dyn Texture>,
 }
 
 impl Metal {
-    pub fn new(albedo: Vec3) -> Self {
+    pub fn new(albedo: Arc<dyn Texture>) -> Self {
         Metal { albedo }
     }
 }
@@ -67,10 +154,11 @@ impl Scatterable for Metal {
         let mut unit_vector = r_in.direction();
         unit_vector.normalize();
         let reflected = reflect(unit_vector, rec.normal);
-        let scattered = Ray::new(rec.p, reflected, r_in.time());
+        let mut scattered = Ray::new(rec.p, reflected, r_in.time());
+        scattered.differentials = reflect_differentials(r_in, rec);
                 
         if  dot(scattered.direction(), rec.normal) > 0.0 {
-            Some((self.albedo, scattered))
+            Some((albedo_value(self.albedo.as_ref(), r_in, rec), scattered))
         }
         else {
             None
@@ -92,8 +180,6 @@ impl Dielectric {
 impl Scatterable for Dielectric {
     fn scatter(&self, r_in: &Ray, rec: &HitRecord) -> Option<(Vec3, Ray)> {
         let reflected = reflect(r_in.direction(), rec.normal);
-        let scattered: Ray;
-        let reflect_prob: f32;
         let dir_dot_normal = dot(r_in.direction(), rec.normal);
         let cosine = if dir_dot_normal > 0.0 { 
                 self.ref_idx * dir_dot_normal / r_in.direction().length()
@@ -106,43 +192,126 @@ impl Scatterable for Dielectric {
         let ni_over_nt = if dir_dot_normal > 0.0 { self.ref_idx } else { 1.0 / self.ref_idx };
 
         let mut refracted = Vec3::zero();
-        if refract(r_in.direction(), outward_normal, ni_over_nt, &mut refracted) {
-            reflect_prob = schlick(cosine, self.ref_idx);
+        let reflect_prob = if refract(r_in.direction(), outward_normal, ni_over_nt, &mut refracted) {
+            schlick(cosine, self.ref_idx)
         }
         else {
-            reflect_prob = 1.0;
-        }
+            1.0
+        };
 
+        let mut scattered;
         if random::<f32>() < reflect_prob {
             scattered = Ray::new(rec.p, reflected, r_in.time());
+            scattered.differentials = reflect_differentials(r_in, rec);
         }
         else {
             scattered = Ray::new(rec.p, refracted, r_in.time());
+            scattered.differentials = refract_differentials(r_in, rec, outward_normal, ni_over_nt);
         }
 
         Some((Vec3::one(), scattered))
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+// An emitter that absorbs everything arriving at it, e.g. an area light
+#[derive(Clone, Debug)]
+pub struct DiffuseLight {
+    pub emit: Arc<dyn Texture>,
+}
+
+impl DiffuseLight {
+    pub fn new(emit: Arc<dyn Texture>) -> Self {
+        DiffuseLight { emit }
+    }
+}
+
+impl Scatterable for DiffuseLight {
+    fn scatter(&self, _r_in: &Ray, _rec: &HitRecord) -> Option<(Vec3, Ray)> {
+        None
+    }
+
+    fn emitted(&self, rec: &HitRecord) -> Vec3 {
+        self.emit.value(rec.u, rec.v, &rec.p)
+    }
+}
+
+// Scattering inside a participating medium: the phase function picks the new direction
+// and the albedo is the fraction of light that survives each collision. A glowing medium
+// reports its emission in the hit record, since only the medium knows where in its own
+// space the collision landed. Collisions absorb as well as scatter, so weighting the emitted radiance by the absorbed fraction makes the sum
+// over collisions an unbiased estimate of the emission absorbed along the ray.
+#[derive(Clone, Debug)]
+pub struct Volumetric {
+    pub albedo: Vec3,
+    pub phase: PhaseFunction,
+}
+
+impl Volumetric {
+    pub fn new(albedo: Vec3, phase: PhaseFunction) -> Self {
+        Volumetric { albedo, phase }
+    }
+}
+
+impl Scatterable for Volumetric {
+    fn scatter(&self, r_in: &Ray, rec: &HitRecord) -> Option<(Vec3, Ray)> {
+        // The sampled pdf matches the phase function exactly, leaving just the albedo as weight
+        let (direction, _pdf) = self.phase.sample(r_in.direction());
+        Some((self.albedo, Ray::new(rec.p, direction, r_in.time())))
+    }
+
+    fn emitted(&self, rec: &HitRecord) -> Vec3 {
+        match rec.emission {
+            Some(emission) => (Vec3::one() - self.albedo) * emission,
+            None => Vec3::zero()
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
 pub enum Material {
     Lambertian(Lambertian),
     Metal(Metal),
     Dielectric(Dielectric),
+    Volumetric(Volumetric),
+    DiffuseLight(DiffuseLight),
 }
 
 impl Material {
     pub fn lambertian(albedo: Vec3) -> Self {
+        Material::lambertian_texture(Arc::new(SolidColor::new(albedo)))
+    }
+
+    pub fn lambertian_texture(albedo: Arc<dyn Texture>) -> Self {
         Material::Lambertian(Lambertian::new(albedo))
     }
 
     pub fn metal(albedo: Vec3) -> Self {
+        Material::metal_texture(Arc::new(SolidColor::new(albedo)))
+    }
+
+    pub fn metal_texture(albedo: Arc<dyn Texture>) -> Self {
         Material::Metal(Metal::new(albedo))
     }
 
     pub fn dielectric(ref_idx: f32) -> Self {
         Material::Dielectric(Dielectric::new(ref_idx))
     }
+
+    pub fn isotropic(albedo: Vec3) -> Self {
+        Material::volumetric(albedo, PhaseFunction::Isotropic)
+    }
+
+    pub fn volumetric(albedo: Vec3, phase: PhaseFunction) -> Self {
+        Material::Volumetric(Volumetric::new(albedo, phase))
+    }
+
+    pub fn diffuse_light(color: Vec3) -> Self {
+        Material::diffuse_light_texture(Arc::new(SolidColor::new(color)))
+    }
+
+    pub fn diffuse_light_texture(emit: Arc<dyn Texture>) -> Self {
+        Material::DiffuseLight(DiffuseLight::new(emit))
+    }
 }
 
 impl Scatterable for Material {
@@ -151,6 +320,18 @@ impl Scatterable for Material {
             Material::Lambertian(ref inner) => inner.scatter(r_in, rec),
             Material::Metal(ref inner) => inner.scatter(r_in, rec),
             Material::Dielectric(ref inner) => inner.scatter(r_in, rec),
+            Material::Volumetric(ref inner) => inner.scatter(r_in, rec),
+            Material::DiffuseLight(ref inner) => inner.scatter(r_in, rec),
+        }
+    }
+
+    fn emitted(&self, rec: &HitRecord) -> Vec3 {
+        match *self {
+            Material::Lambertian(ref inner) => inner.emitted(rec),
+            Material::Metal(ref inner) => inner.emitted(rec),
+            Material::Dielectric(ref inner) => inner.emitted(rec),
+            Material::Volumetric(ref inner) => inner.emitted(rec),
+            Material::DiffuseLight(ref inner) => inner.emitted(rec),
         }
     }
 }
\ No newline at end of file
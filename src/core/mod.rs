@@ -2,10 +2,37 @@ pub mod vec3;
 pub mod ray;
 pub mod hitable;
 pub mod sphere;
+pub mod rect;
+pub mod cuboid;
+pub mod flip_normals;
+pub mod plane;
+pub mod quadric;
+pub mod torus;
+pub mod polynomial;
+pub mod csg;
+pub mod sdf;
+pub mod heightfield;
+pub mod constant_medium;
+pub mod voxel_grid;
+pub mod grid_medium;
+pub mod triangle;
+pub mod triangle_mesh;
+pub mod translate;
+pub mod rotate;
+pub mod transform;
+pub mod transformed;
+pub mod quaternion;
+pub mod animated_transform;
+pub mod animated;
+pub mod instance;
 pub mod hitable_list;
 pub mod camera;
 pub mod sampling;
+pub mod perlin;
+pub mod texture;
+pub mod image_texture;
 pub mod material;
+pub mod phase_function;
 pub mod image;
 pub mod aabb;
 pub mod bvh;
\ No newline at end of file
@@ -0,0 +1,111 @@
+use super::vec3::*;
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+
+const POINT_COUNT: usize = 256;
+
+// Gradient noise: random unit vectors on a lattice, hashed through three permutation tables,
+// with their dot products against the offset to each corner blended by a Hermite curve
+#[derive(Clone, Debug)]
+pub struct Perlin {
+    gradients: Vec<Vec3>,
+    perm_x: Vec<usize>,
+    perm_y: Vec<usize>,
+    perm_z: Vec<usize>,
+}
+
+impl Perlin {
+    pub fn new() -> Self {
+        Perlin::from_rng(&mut rand::thread_rng())
+    }
+
+    // The same seed always produces the same pattern, for looks that must be reproducible
+    pub fn with_seed(seed: u64) -> Self {
+        Perlin::from_rng(&mut StdRng::seed_from_u64(seed))
+    }
+
+    pub fn from_rng<R: Rng>(rng: &mut R) -> Self {
+        let gradients = (0..POINT_COUNT)
+            .map(|_| loop {
+                let v = Vec3::new(rng.gen_range(-1.0, 1.0), rng.gen_range(-1.0, 1.0), rng.gen_range(-1.0, 1.0));
+                let length_squared = v.squared_length();
+                if length_squared > 1e-4 && length_squared <= 1.0 {
+                    break unit_vector(v)
+                }
+            })
+            .collect();
+
+        let mut permutation = || {
+            let mut perm: Vec<usize> = (0..POINT_COUNT).collect();
+            perm.shuffle(rng);
+            perm
+        };
+        let (perm_x, perm_y, perm_z) = (permutation(), permutation(), permutation());
+
+        Perlin { gradients, perm_x, perm_y, perm_z }
+    }
+
+    // Smooth noise in roughly [-1, 1], zero at every lattice point
+    pub fn noise(&self, p: &Vec3) -> f32 {
+        let (fx, fy, fz) = (p.x().floor(), p.y().floor(), p.z().floor());
+        let (u, v, w) = (p.x() - fx, p.y() - fy, p.z() - fz);
+        let (i, j, k) = (fx as i64, fy as i64, fz as i64);
+
+        let mut corners = [[[Vec3::zero(); 2]; 2]; 2];
+        for (di, plane) in corners.iter_mut().enumerate() {
+            for (dj, row) in plane.iter_mut().enumerate() {
+                for (dk, gradient) in row.iter_mut().enumerate() {
+                    let wrap = |n: i64, d: usize| (n + d as i64).rem_euclid(POINT_COUNT as i64) as usize;
+                    let hash = self.perm_x[wrap(i, di)] ^ self.perm_y[wrap(j, dj)] ^ self.perm_z[wrap(k, dk)];
+                    *gradient = self.gradients[hash];
+                }
+            }
+        }
+
+        trilinear_gradients(&corners, u, v, w)
+    }
+
+    // Sum of `depth` octaves, each at double the frequency and half the weight of the
+    // last; the absolute value gives the creased look of smoke and veined stone
+    pub fn turbulence(&self, p: &Vec3, depth: u32) -> f32 {
+        let mut accumulated = 0.0;
+        let mut point = *p;
+        let mut weight = 1.0;
+
+        for _ in 0..depth {
+            accumulated += weight * self.noise(&point);
+            weight *= 0.5;
+            point *= 2.0;
+        }
+
+        accumulated.abs()
+    }
+}
+
+impl Default for Perlin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn trilinear_gradients(corners: &[[[Vec3; 2]; 2]; 2], u: f32, v: f32, w: f32) -> f32 {
+    // Hermite smoothing hides the lattice by making the blend's derivative vanish at cell edges
+    let hermite = |t: f32| t * t * (3.0 - 2.0 * t);
+    let (uu, vv, ww) = (hermite(u), hermite(v), hermite(w));
+
+    let mut accumulated = 0.0;
+    for (i, plane) in corners.iter().enumerate() {
+        for (j, row) in plane.iter().enumerate() {
+            for (k, gradient) in row.iter().enumerate() {
+                let (fi, fj, fk) = (i as f32, j as f32, k as f32);
+                let offset = Vec3::new(u - fi, v - fj, w - fk);
+                accumulated += (fi * uu + (1.0 - fi) * (1.0 - uu))
+                    * (fj * vv + (1.0 - fj) * (1.0 - vv))
+                    * (fk * ww + (1.0 - fk) * (1.0 - ww))
+                    * dot(*gradient, offset);
+            }
+        }
+    }
+    accumulated
+}
\ No newline at end of file
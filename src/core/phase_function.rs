@@ -0,0 +1,81 @@
+use super::vec3::*;
+use rand::random;
+use std::f32;
+
+// Angular distribution of light scattered inside a participating medium. Directions are
+// both taken along the propagation of light, so cos_theta = 1 means no deflection and a
+// positive asymmetry g favours forward scattering.
+#[derive(Clone, Copy, Debug)]
+pub enum PhaseFunction {
+    Isotropic,
+    HenyeyGreenstein { g: f32 },
+    // Blend of a forward and a backward lobe, weighted `weight` towards the first
+    DoubleHenyeyGreenstein { g1: f32, g2: f32, weight: f32 },
+    Rayleigh,
+}
+
+impl PhaseFunction {
+    pub fn henyey_greenstein(g: f32) -> Self {
+        PhaseFunction::HenyeyGreenstein { g: g.clamp(-0.999, 0.999) }
+    }
+
+    pub fn double_henyey_greenstein(g1: f32, g2: f32, weight: f32) -> Self {
+        PhaseFunction::DoubleHenyeyGreenstein {
+            g1: g1.clamp(-0.999, 0.999),
+            g2: g2.clamp(-0.999, 0.999),
+            weight: weight.clamp(0.0, 1.0) }
+    }
+
+    // Probability density per unit solid angle of deflecting by an angle with cosine `cos_theta`
+    pub fn pdf(&self, cos_theta: f32) -> f32 {
+        match *self {
+            PhaseFunction::Isotropic => 0.25 * f32::consts::FRAC_1_PI,
+            PhaseFunction::HenyeyGreenstein { g } => henyey_greenstein(cos_theta, g),
+            PhaseFunction::DoubleHenyeyGreenstein { g1, g2, weight } => {
+                weight * henyey_greenstein(cos_theta, g1) + (1.0 - weight) * henyey_greenstein(cos_theta, g2)
+            },
+            PhaseFunction::Rayleigh => 3.0 / (16.0 * f32::consts::PI) * (1.0 + cos_theta * cos_theta),
+        }
+    }
+
+    // Samples a scattered direction for light travelling along `direction`, returning it
+    // with its pdf. Sampling is exact, so the pdf equals the phase function's value.
+    pub fn sample(&self, direction: Vec3) -> (Vec3, f32) {
+        let cos_theta = match *self {
+            PhaseFunction::Isotropic => 1.0 - 2.0 * random::<f32>(),
+            PhaseFunction::HenyeyGreenstein { g } => sample_henyey_greenstein(g),
+            PhaseFunction::DoubleHenyeyGreenstein { g1, g2, weight } => {
+                sample_henyey_greenstein(if random::<f32>() < weight { g1 } else { g2 })
+            },
+            PhaseFunction::Rayleigh => {
+                // Inverts the CDF (mu^3 + 3mu + 4) / 8 with Cardano's formula
+                let u = 4.0 * random::<f32>() - 2.0;
+                let root = f32::sqrt(u * u + 1.0);
+                f32::cbrt(u + root) + f32::cbrt(u - root)
+            },
+        }.clamp(-1.0, 1.0);
+
+        let w = unit_vector(direction);
+        let (tangent, bitangent) = orthonormal_basis(w);
+        let sin_theta = f32::sqrt(f32::max(0.0, 1.0 - cos_theta * cos_theta));
+        let phi = 2.0 * f32::consts::PI * random::<f32>();
+        let scattered = sin_theta * f32::cos(phi) * tangent + sin_theta * f32::sin(phi) * bitangent + cos_theta * w;
+
+        (scattered, self.pdf(cos_theta))
+    }
+}
+
+fn henyey_greenstein(cos_theta: f32, g: f32) -> f32 {
+    let denominator = 1.0 + g * g - 2.0 * g * cos_theta;
+    0.25 * f32::consts::FRAC_1_PI * (1.0 - g * g) / (denominator * f32::sqrt(denominator))
+}
+
+fn sample_henyey_greenstein(g: f32) -> f32 {
+    let xi = random::<f32>();
+    if g.abs() < 0.001 {
+        return 1.0 - 2.0 * xi
+    }
+
+    let s = (1.0 - g * g) / (1.0 - g + 2.0 * g * xi);
+    (1.0 + g * g - s * s) / (2.0 * g)
+}
\ No newline at end of file
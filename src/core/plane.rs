@@ -0,0 +1,134 @@
+use super::vec3::*;
+use super::ray::*;
+use super::hitable::*;
+use super::material::*;
+use super::aabb::*;
+use std::f32;
+
+// Minimum half-thickness of a disk's bounding box along axes it lies flat against
+const DISK_PADDING: f32 = 0.0001;
+
+// An infinite plane. It has no bounding box, so BVHNode keeps it out of the hierarchy.
+#[derive(Clone, Debug)]
+pub struct Plane {
+    pub point: Vec3,
+    pub normal: Vec3,
+    pub material: Material,
+    tangent: Vec3,
+    bitangent: Vec3,
+}
+
+impl Plane {
+    pub fn new(point: Vec3, normal: Vec3, mat: Material) -> Self {
+        let normal = unit_vector(normal);
+        let (tangent, bitangent) = orthonormal_basis(normal);
+        Plane { point, normal, material: mat, tangent, bitangent }
+    }
+}
+
+impl Hitable for Plane {
+    fn hit(&self, r: Ray, t_min: f32, t_max: f32) -> Option<HitRecord<'_>> {
+        let denom = dot(self.normal, r.direction());
+        if denom.abs() < f32::EPSILON {
+            return None
+        }
+
+        let t = dot(self.point - r.origin(), self.normal) / denom;
+        if t < t_min || t > t_max {
+            return None
+        }
+
+        let p = r.point_at_parameter(t);
+        let offset = p - self.point;
+        let rec = HitRecord {
+            t,
+            u: dot(offset, self.tangent),
+            v: dot(offset, self.bitangent),
+            p,
+            normal: self.normal,
+            dpdu: self.tangent,
+            dpdv: self.bitangent,
+            color: None,
+            emission: None,
+            material: &self.material };
+
+        Some(rec)
+    }
+
+    fn bounding_box(&self, _t0: f32, _t1: f32) -> Option<AABB> {
+        None
+    }
+
+    fn clone_to_box(&self) -> Box<dyn Hitable> {
+        Box::new(self.clone())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Disk {
+    pub center: Vec3,
+    pub normal: Vec3,
+    pub radius: f32,
+    pub material: Material,
+    tangent: Vec3,
+    bitangent: Vec3,
+}
+
+impl Disk {
+    pub fn new(center: Vec3, normal: Vec3, radius: f32, mat: Material) -> Self {
+        let normal = unit_vector(normal);
+        let (tangent, bitangent) = orthonormal_basis(normal);
+        Disk { center, normal, radius, material: mat, tangent, bitangent }
+    }
+}
+
+impl Hitable for Disk {
+    fn hit(&self, r: Ray, t_min: f32, t_max: f32) -> Option<HitRecord<'_>> {
+        let denom = dot(self.normal, r.direction());
+        if denom.abs() < f32::EPSILON {
+            return None
+        }
+
+        let t = dot(self.center - r.origin(), self.normal) / denom;
+        if t < t_min || t > t_max {
+            return None
+        }
+
+        let p = r.point_at_parameter(t);
+        let offset = p - self.center;
+        let dist_squared = offset.squared_length();
+        if dist_squared > self.radius * self.radius {
+            return None
+        }
+
+        // u runs around the rim, v from the center outwards
+        let phi = f32::atan2(dot(offset, self.bitangent), dot(offset, self.tangent));
+        let phi = if phi < 0.0 { phi + 2.0 * f32::consts::PI } else { phi };
+        let rec = HitRecord {
+            t,
+            u: phi / (2.0 * f32::consts::PI),
+            v: f32::sqrt(dist_squared) / self.radius,
+            p,
+            normal: self.normal,
+            dpdu: 2.0 * f32::consts::PI * (dot(offset, self.tangent) * self.bitangent - dot(offset, self.bitangent) * self.tangent),
+            dpdv: if dist_squared > 0.0 { self.radius / f32::sqrt(dist_squared) * offset } else { Vec3::zero() },
+            color: None,
+            emission: None,
+            material: &self.material };
+
+        Some(rec)
+    }
+
+    fn bounding_box(&self, _t0: f32, _t1: f32) -> Option<AABB> {
+        let mut extent = Vec3::zero();
+        for a in 0..3 {
+            extent[a] = f32::max(self.radius * f32::sqrt(f32::max(0.0, 1.0 - self.normal[a] * self.normal[a])), DISK_PADDING);
+        }
+
+        Some(AABB::new(self.center - extent, self.center + extent))
+    }
+
+    fn clone_to_box(&self) -> Box<dyn Hitable> {
+        Box::new(self.clone())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,200 @@
+use std::f64;
+
+// Real roots of a*t^2 + b*t + c = 0 in ascending order
+pub fn solve_quadratic(a: f32, b: f32, c: f32) -> Option<(f32, f32)> {
+    if a.abs() < 1e-12 {
+        if b.abs() < 1e-12 {
+            return None
+        }
+        let t = -c / b;
+        return Some((t, t))
+    }
+
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant < 0.0 {
+        return None
+    }
+
+    // Avoids the cancellation in (-b +- sqrt(disc)) / 2a when b is large
+    let q = -0.5 * (b + f32::sqrt(discriminant).copysign(b));
+    if q == 0.0 {
+        return Some((0.0, 0.0))
+    }
+
+    let (t0, t1) = (q / a, c / q);
+    if t0 < t1 { Some((t0, t1)) } else { Some((t1, t0)) }
+}
+
+fn push_quadratic_roots(a: f64, b: f64, c: f64, roots: &mut Vec<f64>) {
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant < 0.0 {
+        return
+    }
+
+    let q = -0.5 * (b + f64::sqrt(discriminant).copysign(b));
+    if q == 0.0 {
+        roots.push(0.0);
+        return
+    }
+    roots.push(q / a);
+    roots.push(c / q);
+}
+
+// Real roots of the monic cubic x^3 + a*x^2 + b*x + c = 0
+pub fn solve_cubic(a: f64, b: f64, c: f64) -> Vec<f64> {
+    let q = (a * a - 3.0 * b) / 9.0;
+    let r = (2.0 * a * a * a - 9.0 * a * b + 27.0 * c) / 54.0;
+    let q3 = q * q * q;
+
+    if r * r < q3 {
+        let theta = f64::acos((r / f64::sqrt(q3)).clamp(-1.0, 1.0));
+        let scale = -2.0 * f64::sqrt(q);
+        let two_pi = 2.0 * f64::consts::PI;
+        vec![
+            scale * f64::cos(theta / 3.0) - a / 3.0,
+            scale * f64::cos((theta + two_pi) / 3.0) - a / 3.0,
+            scale * f64::cos((theta - two_pi) / 3.0) - a / 3.0]
+    }
+    else {
+        let big_a = -r.signum() * f64::cbrt(r.abs() + f64::sqrt(r * r - q3));
+        let big_b = if big_a != 0.0 { q / big_a } else { 0.0 };
+        vec![big_a + big_b - a / 3.0]
+    }
+}
+
+// Real roots of the monic quartic x^4 + a*x^3 + b*x^2 + c*x + d = 0 in ascending order,
+// using Ferrari's method followed by Newton polishing against the original polynomial.
+pub fn solve_quartic(a: f64, b: f64, c: f64, d: f64) -> Vec<f64> {
+    // Depress with x = y - a/4 to y^4 + p*y^2 + q*y + r = 0
+    let a2 = a * a;
+    let p = b - 3.0 * a2 / 8.0;
+    let q = c - a * b / 2.0 + a2 * a / 8.0;
+    let r = d - a * c / 4.0 + a2 * b / 16.0 - 3.0 * a2 * a2 / 256.0;
+
+    let mut roots = Vec::with_capacity(4);
+
+    if q.abs() < 1e-12 {
+        // Biquadratic: solve for z = y^2
+        let mut z = Vec::with_capacity(2);
+        push_quadratic_roots(1.0, p, r, &mut z);
+        for &zi in z.iter().filter(|&&zi| zi >= 0.0) {
+            let y = f64::sqrt(zi);
+            roots.push(y);
+            roots.push(-y);
+        }
+    }
+    else {
+        // The resolvent cubic always has a positive root when q != 0
+        let m = solve_cubic(p, p * p / 4.0 - r, -q * q / 8.0)
+            .into_iter()
+            .fold(f64::NEG_INFINITY, f64::max);
+        if m <= 0.0 {
+            return roots
+        }
+
+        let s = f64::sqrt(2.0 * m);
+        push_quadratic_roots(1.0, s, p / 2.0 + m - q / (2.0 * s), &mut roots);
+        push_quadratic_roots(1.0, -s, p / 2.0 + m + q / (2.0 * s), &mut roots);
+    }
+
+    for root in roots.iter_mut() {
+        let eval = |x: f64| (((x + a) * x + b) * x + c) * x + d;
+        let mut x = *root - a / 4.0;
+        for _ in 0..4 {
+            let df = ((4.0 * x + 3.0 * a) * x + 2.0 * b) * x + c;
+            if df == 0.0 {
+                break
+            }
+            let next = x - eval(x) / df;
+            if eval(next).abs() >= eval(x).abs() {
+                break
+            }
+            x = next;
+        }
+        *root = x;
+    }
+
+    roots.sort_by(|x, y| x.partial_cmp(y).unwrap_or(std::cmp::Ordering::Equal));
+    roots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Coefficients of the monic polynomial with the given roots
+    fn monic_from_roots(roots: &[f64; 4]) -> (f64, f64, f64, f64) {
+        let mut coefficients = vec![1.0];
+        for &root in roots.iter() {
+            let mut next = vec![0.0; coefficients.len() + 1];
+            for (i, &c) in coefficients.iter().enumerate() {
+                next[i] += c;
+                next[i + 1] -= root * c;
+            }
+            coefficients = next;
+        }
+        (coefficients[1], coefficients[2], coefficients[3], coefficients[4])
+    }
+
+    fn assert_roots(found: &[f64], expected: &[f64], tolerance: f64) {
+        assert_eq!(found.len(), expected.len(), "found {:?}, expected {:?}", found, expected);
+        for (f, e) in found.iter().zip(expected.iter()) {
+            assert!((f - e).abs() <= tolerance * f64::max(1.0, e.abs()), "found {:?}, expected {:?}", found, expected);
+        }
+    }
+
+    fn has_root_near(found: &[f64], expected: f64, tolerance: f64) -> bool {
+        found.iter().any(|f| (f - expected).abs() <= tolerance)
+    }
+
+    #[test]
+    fn quartic_with_four_distinct_roots() {
+        let (a, b, c, d) = monic_from_roots(&[1.0, 2.0, 3.0, 4.0]);
+        assert_roots(&solve_quartic(a, b, c, d), &[1.0, 2.0, 3.0, 4.0], 1e-9);
+
+        let (a, b, c, d) = monic_from_roots(&[-5.0, -0.5, 0.25, 7.0]);
+        assert_roots(&solve_quartic(a, b, c, d), &[-5.0, -0.5, 0.25, 7.0], 1e-9);
+
+        // Biquadratic, where the cubic term of the depressed quartic vanishes
+        let (a, b, c, d) = monic_from_roots(&[-2.0, -1.0, 1.0, 2.0]);
+        assert_roots(&solve_quartic(a, b, c, d), &[-2.0, -1.0, 1.0, 2.0], 1e-9);
+    }
+
+    #[test]
+    fn quartic_with_double_roots() {
+        let (a, b, c, d) = monic_from_roots(&[1.0, 1.0, 2.0, -3.0]);
+        let roots = solve_quartic(a, b, c, d);
+        assert!(has_root_near(&roots, -3.0, 1e-9) && has_root_near(&roots, 2.0, 1e-9), "{:?}", roots);
+        assert!(has_root_near(&roots, 1.0, 1e-6), "{:?}", roots);
+
+        let (a, b, c, d) = monic_from_roots(&[-2.0, -2.0, 1.5, 1.5]);
+        let roots = solve_quartic(a, b, c, d);
+        assert!(has_root_near(&roots, -2.0, 1e-6) && has_root_near(&roots, 1.5, 1e-6), "{:?}", roots);
+    }
+
+    #[test]
+    fn quartic_with_no_real_roots() {
+        // (x^2 + 1)(x^2 + 4)
+        assert!(solve_quartic(0.0, 5.0, 0.0, 4.0).is_empty());
+        // (x^2 + 1)((x - 2)^2 + 1)
+        assert!(solve_quartic(-4.0, 6.0, -4.0, 5.0).is_empty());
+    }
+
+    #[test]
+    fn quartic_with_near_degenerate_leading_coefficient() {
+        // 1e-6 * x^4 + ... has roots 1, 2, 3 and one far out at -1e6; dividing through to
+        // make it monic leaves very large coefficients
+        let (a, b, c, d) = monic_from_roots(&[1.0, 2.0, 3.0, -1.0e6]);
+        let roots = solve_quartic(a, b, c, d);
+        assert_eq!(roots.len(), 4, "{:?}", roots);
+        assert!((roots[0] + 1.0e6).abs() < 1e-3, "{:?}", roots);
+        assert_roots(&roots[1..], &[1.0, 2.0, 3.0], 1e-6);
+    }
+
+    #[test]
+    fn quadratic_roots_are_ordered() {
+        assert_eq!(solve_quadratic(1.0, -3.0, 2.0), Some((1.0, 2.0)));
+        assert_eq!(solve_quadratic(-1.0, 3.0, -2.0), Some((1.0, 2.0)));
+        assert_eq!(solve_quadratic(1.0, 0.0, 1.0), None);
+    }
+}
\ No newline at end of file
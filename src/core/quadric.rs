@@ -0,0 +1,399 @@
+use super::vec3::*;
+use super::ray::*;
+use super::hitable::*;
+use super::material::*;
+use super::aabb::*;
+use super::polynomial::*;
+use std::f32;
+
+// Surfaces of revolution about the local y axis, all of the form
+// x^2 + z^2 + b*y^2 + c*y + d = 0, clipped to [y_min, y_max] and swept by phi_max.
+#[derive(Clone, Copy, Debug)]
+struct QuadricSurface {
+    b: f32,
+    c: f32,
+    d: f32,
+    y_min: f32,
+    y_max: f32,
+    max_radius: f32,
+}
+
+// A disk closing one end of a quadric, facing along +y (facing = 1) or -y (facing = -1)
+#[derive(Clone, Copy, Debug)]
+struct Cap {
+    y: f32,
+    radius: f32,
+    facing: f32,
+}
+
+// How much of a quadric is drawn: the angle swept around its axis, and whether its open
+// ends are closed off with disks
+#[derive(Clone, Copy, Debug)]
+pub struct Sweep {
+    pub phi_max: f32,
+    pub capped: bool,
+}
+
+impl Default for Sweep {
+    fn default() -> Self {
+        Sweep { phi_max: 2.0 * f32::consts::PI, capped: false }
+    }
+}
+
+pub trait Swept: Sized {
+    fn sweep_mut(&mut self) -> &mut Sweep;
+
+    fn with_caps(mut self) -> Self {
+        self.sweep_mut().capped = true;
+        self
+    }
+
+    // Limits the sweep around the axis to an angle in degrees
+    fn with_phi_max(mut self, angle: f32) -> Self {
+        self.sweep_mut().phi_max = degrees_to_radians(angle);
+        self
+    }
+}
+
+fn phi_of(p: Vec3) -> f32 {
+    let phi = f32::atan2(p.z(), p.x());
+    if phi < 0.0 { phi + 2.0 * f32::consts::PI } else { phi }
+}
+
+fn degrees_to_radians(angle: f32) -> f32 {
+    angle * f32::consts::PI / 180.0
+}
+
+#[allow(clippy::too_many_arguments)]
+fn hit_quadric<'a>(
+    center: Vec3,
+    surface: &QuadricSurface,
+    caps: &[Option<Cap>; 2],
+    phi_max: f32,
+    material: &'a Material,
+    r: Ray,
+    t_min: f32,
+    t_max: f32) -> Option<HitRecord<'a>> {
+
+    let o = r.origin() - center;
+    let d = r.direction();
+    let mut closest_so_far = t_max;
+    let mut best_hit: Option<HitRecord> = None;
+
+    let a = d.x() * d.x() + d.z() * d.z() + surface.b * d.y() * d.y();
+    let b = 2.0 * (o.x() * d.x() + o.z() * d.z() + surface.b * o.y() * d.y()) + surface.c * d.y();
+    let c = o.x() * o.x() + o.z() * o.z() + surface.b * o.y() * o.y() + surface.c * o.y() + surface.d;
+
+    if let Some((t0, t1)) = solve_quadratic(a, b, c) {
+        for &t in [t0, t1].iter() {
+            if t <= t_min || t >= closest_so_far {
+                continue
+            }
+
+            let p = o + t * d;
+            let phi = phi_of(p);
+            if p.y() < surface.y_min || p.y() > surface.y_max || phi > phi_max {
+                continue
+            }
+
+            // The gradient vanishes at a cone's apex, where the surface points along the axis
+            // away from the rest of the shape
+            let gradient = Vec3::new(p.x(), surface.b * p.y() + 0.5 * surface.c, p.z());
+            let normal = if gradient.squared_length() > 1e-12 * surface.max_radius * surface.max_radius {
+                unit_vector(gradient)
+            }
+            else if p.y() > 0.5 * (surface.y_min + surface.y_max) {
+                Vec3::unit_y()
+            }
+            else {
+                -1.0 * Vec3::unit_y()
+            };
+
+            // Moving up the surface the radius changes by dr/dy = -(2by + c) / 2r, carrying
+            // the point outwards along its own radial direction (x, 0, z) / r
+            let radius_squared = p.x() * p.x() + p.z() * p.z();
+            let radial_rate = if radius_squared > 0.0 { -(2.0 * surface.b * p.y() + surface.c) / (2.0 * radius_squared) } else { 0.0 };
+
+            closest_so_far = t;
+            best_hit = Some(HitRecord {
+                t,
+                u: phi / phi_max,
+                v: (p.y() - surface.y_min) / (surface.y_max - surface.y_min),
+                p: r.point_at_parameter(t),
+                normal,
+                dpdu: phi_max * Vec3::new(-p.z(), 0.0, p.x()),
+                dpdv: (surface.y_max - surface.y_min) * Vec3::new(radial_rate * p.x(), 1.0, radial_rate * p.z()),
+                color: None,
+                emission: None,
+                material });
+            break
+        }
+    }
+
+    for cap in caps.iter().flatten() {
+        if d.y().abs() < f32::EPSILON {
+            continue
+        }
+
+        let t = (cap.y - o.y()) / d.y();
+        if t <= t_min || t >= closest_so_far {
+            continue
+        }
+
+        let p = o + t * d;
+        let dist_squared = p.x() * p.x() + p.z() * p.z();
+        let phi = phi_of(p);
+        if dist_squared > cap.radius * cap.radius || phi > phi_max {
+            continue
+        }
+
+        let dist = f32::sqrt(dist_squared);
+        let radial = if dist > 0.0 { Vec3::new(p.x(), 0.0, p.z()) / dist } else { Vec3::zero() };
+
+        closest_so_far = t;
+        best_hit = Some(HitRecord {
+            t,
+            u: phi / phi_max,
+            v: dist / cap.radius,
+            p: r.point_at_parameter(t),
+            normal: Vec3::new(0.0, cap.facing, 0.0),
+            dpdu: phi_max * Vec3::new(-p.z(), 0.0, p.x()),
+            dpdv: cap.radius * radial,
+            color: None,
+            emission: None,
+            material });
+    }
+
+    best_hit
+}
+
+fn quadric_bounding_box(center: Vec3, surface: &QuadricSurface) -> AABB {
+    AABB::new(
+        center + Vec3::new(-surface.max_radius, surface.y_min, -surface.max_radius),
+        center + Vec3::new(surface.max_radius, surface.y_max, surface.max_radius))
+}
+
+// A cylinder standing on `center`, extending `height` along +y
+#[derive(Clone, Debug)]
+pub struct Cylinder {
+    pub center: Vec3,
+    pub radius: f32,
+    pub height: f32,
+    pub sweep: Sweep,
+    pub material: Material,
+}
+
+impl Cylinder {
+    pub fn new(center: Vec3, radius: f32, height: f32, mat: Material) -> Self {
+        Cylinder { center, radius, height, sweep: Sweep::default(), material: mat }
+    }
+
+    fn surface(&self) -> QuadricSurface {
+        QuadricSurface { b: 0.0, c: 0.0, d: -self.radius * self.radius, y_min: 0.0, y_max: self.height, max_radius: self.radius }
+    }
+
+    fn caps(&self) -> [Option<Cap>; 2] {
+        if self.sweep.capped {
+            [Some(Cap { y: 0.0, radius: self.radius, facing: -1.0 }), Some(Cap { y: self.height, radius: self.radius, facing: 1.0 })]
+        }
+        else {
+            [None, None]
+        }
+    }
+}
+
+impl Swept for Cylinder {
+    fn sweep_mut(&mut self) -> &mut Sweep {
+        &mut self.sweep
+    }
+}
+
+impl Hitable for Cylinder {
+    fn hit(&self, r: Ray, t_min: f32, t_max: f32) -> Option<HitRecord<'_>> {
+        hit_quadric(self.center, &self.surface(), &self.caps(), self.sweep.phi_max, &self.material, r, t_min, t_max)
+    }
+
+    fn bounding_box(&self, _t0: f32, _t1: f32) -> Option<AABB> {
+        Some(quadric_bounding_box(self.center, &self.surface()))
+    }
+
+    fn clone_to_box(&self) -> Box<dyn Hitable> {
+        Box::new(self.clone())
+    }
+}
+
+// A cone with its base on `center` and its apex `height` above it
+#[derive(Clone, Debug)]
+pub struct Cone {
+    pub center: Vec3,
+    pub radius: f32,
+    pub height: f32,
+    pub sweep: Sweep,
+    pub material: Material,
+}
+
+impl Cone {
+    pub fn new(center: Vec3, radius: f32, height: f32, mat: Material) -> Self {
+        Cone { center, radius, height, sweep: Sweep::default(), material: mat }
+    }
+
+    fn surface(&self) -> QuadricSurface {
+        let k2 = (self.radius * self.radius) / (self.height * self.height);
+        QuadricSurface {
+            b: -k2,
+            c: 2.0 * k2 * self.height,
+            d: -k2 * self.height * self.height,
+            y_min: 0.0,
+            y_max: self.height,
+            max_radius: self.radius }
+    }
+
+    fn caps(&self) -> [Option<Cap>; 2] {
+        if self.sweep.capped {
+            [Some(Cap { y: 0.0, radius: self.radius, facing: -1.0 }), None]
+        }
+        else {
+            [None, None]
+        }
+    }
+}
+
+impl Swept for Cone {
+    fn sweep_mut(&mut self) -> &mut Sweep {
+        &mut self.sweep
+    }
+}
+
+impl Hitable for Cone {
+    fn hit(&self, r: Ray, t_min: f32, t_max: f32) -> Option<HitRecord<'_>> {
+        hit_quadric(self.center, &self.surface(), &self.caps(), self.sweep.phi_max, &self.material, r, t_min, t_max)
+    }
+
+    fn bounding_box(&self, _t0: f32, _t1: f32) -> Option<AABB> {
+        Some(quadric_bounding_box(self.center, &self.surface()))
+    }
+
+    fn clone_to_box(&self) -> Box<dyn Hitable> {
+        Box::new(self.clone())
+    }
+}
+
+// A paraboloid with its vertex on `center`, opening along +y to `radius` at `height`
+#[derive(Clone, Debug)]
+pub struct Paraboloid {
+    pub center: Vec3,
+    pub radius: f32,
+    pub height: f32,
+    pub sweep: Sweep,
+    pub material: Material,
+}
+
+impl Paraboloid {
+    pub fn new(center: Vec3, radius: f32, height: f32, mat: Material) -> Self {
+        Paraboloid { center, radius, height, sweep: Sweep::default(), material: mat }
+    }
+
+    fn surface(&self) -> QuadricSurface {
+        QuadricSurface {
+            b: 0.0,
+            c: -self.radius * self.radius / self.height,
+            d: 0.0,
+            y_min: 0.0,
+            y_max: self.height,
+            max_radius: self.radius }
+    }
+
+    fn caps(&self) -> [Option<Cap>; 2] {
+        if self.sweep.capped {
+            [Some(Cap { y: self.height, radius: self.radius, facing: 1.0 }), None]
+        }
+        else {
+            [None, None]
+        }
+    }
+}
+
+impl Swept for Paraboloid {
+    fn sweep_mut(&mut self) -> &mut Sweep {
+        &mut self.sweep
+    }
+}
+
+impl Hitable for Paraboloid {
+    fn hit(&self, r: Ray, t_min: f32, t_max: f32) -> Option<HitRecord<'_>> {
+        hit_quadric(self.center, &self.surface(), &self.caps(), self.sweep.phi_max, &self.material, r, t_min, t_max)
+    }
+
+    fn bounding_box(&self, _t0: f32, _t1: f32) -> Option<AABB> {
+        Some(quadric_bounding_box(self.center, &self.surface()))
+    }
+
+    fn clone_to_box(&self) -> Box<dyn Hitable> {
+        Box::new(self.clone())
+    }
+}
+
+// A hyperboloid of one sheet centered on `center`, with `waist_radius` at its middle
+// widening to `end_radius` at y = +-half_height. end_radius must exceed waist_radius.
+#[derive(Clone, Debug)]
+pub struct Hyperboloid {
+    pub center: Vec3,
+    pub waist_radius: f32,
+    pub end_radius: f32,
+    pub half_height: f32,
+    pub sweep: Sweep,
+    pub material: Material,
+}
+
+impl Hyperboloid {
+    pub fn new(center: Vec3, waist_radius: f32, end_radius: f32, half_height: f32, mat: Material) -> Self {
+        Hyperboloid {
+            center,
+            waist_radius,
+            end_radius,
+            half_height,
+            sweep: Sweep::default(),
+            material: mat }
+    }
+
+    fn surface(&self) -> QuadricSurface {
+        let s = (self.end_radius * self.end_radius - self.waist_radius * self.waist_radius) / (self.half_height * self.half_height);
+        QuadricSurface {
+            b: -s,
+            c: 0.0,
+            d: -self.waist_radius * self.waist_radius,
+            y_min: -self.half_height,
+            y_max: self.half_height,
+            max_radius: self.end_radius }
+    }
+
+    fn caps(&self) -> [Option<Cap>; 2] {
+        if self.sweep.capped {
+            [Some(Cap { y: -self.half_height, radius: self.end_radius, facing: -1.0 }),
+             Some(Cap { y: self.half_height, radius: self.end_radius, facing: 1.0 })]
+        }
+        else {
+            [None, None]
+        }
+    }
+}
+
+impl Swept for Hyperboloid {
+    fn sweep_mut(&mut self) -> &mut Sweep {
+        &mut self.sweep
+    }
+}
+
+impl Hitable for Hyperboloid {
+    fn hit(&self, r: Ray, t_min: f32, t_max: f32) -> Option<HitRecord<'_>> {
+        hit_quadric(self.center, &self.surface(), &self.caps(), self.sweep.phi_max, &self.material, r, t_min, t_max)
+    }
+
+    fn bounding_box(&self, _t0: f32, _t1: f32) -> Option<AABB> {
+        Some(quadric_bounding_box(self.center, &self.surface()))
+    }
+
+    fn clone_to_box(&self) -> Box<dyn Hitable> {
+        Box::new(self.clone())
+    }
+}
\ No newline at end of file
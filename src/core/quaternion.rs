@@ -0,0 +1,72 @@
+use super::vec3::*;
+use super::transform::*;
+use std::f32;
+
+// Unit quaternion representing a rotation, stored as a vector part and a scalar part
+#[derive(Clone, Copy, Debug)]
+pub struct Quaternion {
+    pub v: Vec3,
+    pub w: f32,
+}
+
+impl Quaternion {
+    pub fn new(v: Vec3, w: f32) -> Self {
+        Quaternion { v, w }
+    }
+
+    pub fn identity() -> Self {
+        Quaternion { v: Vec3::zero(), w: 1.0 }
+    }
+
+    // Rotation by an angle in degrees about an arbitrary axis
+    pub fn from_axis_angle(axis: Vec3, angle: f32) -> Self {
+        let half = 0.5 * angle * f32::consts::PI / 180.0;
+        Quaternion { v: f32::sin(half) * unit_vector(axis), w: f32::cos(half) }
+    }
+
+    pub fn dot(&self, other: &Quaternion) -> f32 {
+        dot(self.v, other.v) + self.w * other.w
+    }
+
+    pub fn normalized(&self) -> Self {
+        let length = f32::sqrt(self.dot(self));
+        Quaternion { v: self.v / length, w: self.w / length }
+    }
+
+    // Angle in radians of the rotation taking this orientation to `other`
+    pub fn angle_to(&self, other: &Quaternion) -> f32 {
+        2.0 * f32::acos(self.dot(other).abs().min(1.0))
+    }
+
+    // Constant angular velocity interpolation along the shorter arc
+    pub fn slerp(&self, other: &Quaternion, t: f32) -> Self {
+        let mut cos_theta = self.dot(other);
+        let mut target = *other;
+        if cos_theta < 0.0 {
+            cos_theta = -cos_theta;
+            target = Quaternion { v: -1.0 * target.v, w: -target.w };
+        }
+
+        // Nearly parallel: fall back to a normalized lerp, which avoids dividing by sin(theta)
+        if cos_theta > 0.9995 {
+            let v = (1.0 - t) * self.v + t * target.v;
+            let w = (1.0 - t) * self.w + t * target.w;
+            return Quaternion { v, w }.normalized()
+        }
+
+        let theta = f32::acos(cos_theta);
+        let sin_theta = f32::sin(theta);
+        let a = f32::sin((1.0 - t) * theta) / sin_theta;
+        let b = f32::sin(t * theta) / sin_theta;
+        Quaternion { v: a * self.v + b * target.v, w: a * self.w + b * target.w }
+    }
+
+    pub fn to_matrix(&self) -> Mat4 {
+        let (x, y, z, w) = (self.v.x(), self.v.y(), self.v.z(), self.w);
+        Mat4::new([
+            [1.0 - 2.0 * (y * y + z * z), 2.0 * (x * y - z * w), 2.0 * (x * z + y * w), 0.0],
+            [2.0 * (x * y + z * w), 1.0 - 2.0 * (x * x + z * z), 2.0 * (y * z - x * w), 0.0],
+            [2.0 * (x * z - y * w), 2.0 * (y * z + x * w), 1.0 - 2.0 * (x * x + y * y), 0.0],
+            [0.0, 0.0, 0.0, 1.0] ])
+    }
+}
\ No newline at end of file
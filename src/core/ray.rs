@@ -1,15 +1,31 @@
 use super::vec3::Vec3;
 
+// Two auxiliary rays offset by one pixel in x and y. How far they spread from the main
+// ray by the time it hits a surface gives the pixel's footprint there.
+#[derive(Clone, Copy, Debug)]
+pub struct RayDifferentials {
+    pub rx_origin: Vec3,
+    pub rx_direction: Vec3,
+    pub ry_origin: Vec3,
+    pub ry_direction: Vec3,
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct Ray {
     pub a: Vec3,
     pub b: Vec3,
     pub t: f32,
+    pub differentials: Option<RayDifferentials>,
 }
 
 impl Ray {
     pub fn new(a: Vec3, b: Vec3, t: f32) -> Self {
-        Ray { a, b, t }
+        Ray { a, b, t, differentials: None }
+    }
+
+    pub fn with_differentials(mut self, differentials: RayDifferentials) -> Self {
+        self.differentials = Some(differentials);
+        self
     }
 
     pub fn origin(&self) -> Vec3 {
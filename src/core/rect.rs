@@ -0,0 +1,183 @@
+use super::vec3::*;
+use super::ray::*;
+use super::hitable::*;
+use super::material::*;
+use super::aabb::*;
+
+// Half-thickness given to the bounding boxes of rectangles along their flat axis,
+// so the BVH never has to deal with a degenerate box
+const RECT_PADDING: f32 = 0.0001;
+
+#[derive(Clone, Debug)]
+pub struct XYRect {
+    pub x0: f32,
+    pub x1: f32,
+    pub y0: f32,
+    pub y1: f32,
+    pub k: f32,
+    pub material: Material,
+}
+
+impl XYRect {
+    pub fn new(x0: f32, x1: f32, y0: f32, y1: f32, k: f32, mat: Material) -> Self {
+        XYRect { x0, x1, y0, y1, k, material: mat }
+    }
+}
+
+impl Hitable for XYRect {
+    fn hit(&self, r: Ray, t_min: f32, t_max: f32) -> Option<HitRecord<'_>> {
+        let t = (self.k - r.origin().z()) / r.direction().z();
+        if t < t_min || t > t_max {
+            return None
+        }
+
+        let x = r.origin().x() + t * r.direction().x();
+        let y = r.origin().y() + t * r.direction().y();
+        if x < self.x0 || x > self.x1 || y < self.y0 || y > self.y1 {
+            return None
+        }
+
+        let rec = HitRecord {
+            t,
+            u: (x - self.x0) / (self.x1 - self.x0),
+            v: (y - self.y0) / (self.y1 - self.y0),
+            p: r.point_at_parameter(t),
+            normal: Vec3::unit_z(),
+            dpdu: Vec3::new(self.x1 - self.x0, 0.0, 0.0),
+            dpdv: Vec3::new(0.0, self.y1 - self.y0, 0.0),
+            color: None,
+            emission: None,
+            material: &self.material };
+
+        Some(rec)
+    }
+
+    fn bounding_box(&self, _t0: f32, _t1: f32) -> Option<AABB> {
+        let aabb = AABB::new(
+            Vec3::new(self.x0, self.y0, self.k - RECT_PADDING),
+            Vec3::new(self.x1, self.y1, self.k + RECT_PADDING),
+        );
+
+        Some(aabb)
+    }
+
+    fn clone_to_box(&self) -> Box<dyn Hitable> {
+        Box::new(self.clone())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct XZRect {
+    pub x0: f32,
+    pub x1: f32,
+    pub z0: f32,
+    pub z1: f32,
+    pub k: f32,
+    pub material: Material,
+}
+
+impl XZRect {
+    pub fn new(x0: f32, x1: f32, z0: f32, z1: f32, k: f32, mat: Material) -> Self {
+        XZRect { x0, x1, z0, z1, k, material: mat }
+    }
+}
+
+impl Hitable for XZRect {
+    fn hit(&self, r: Ray, t_min: f32, t_max: f32) -> Option<HitRecord<'_>> {
+        let t = (self.k - r.origin().y()) / r.direction().y();
+        if t < t_min || t > t_max {
+            return None
+        }
+
+        let x = r.origin().x() + t * r.direction().x();
+        let z = r.origin().z() + t * r.direction().z();
+        if x < self.x0 || x > self.x1 || z < self.z0 || z > self.z1 {
+            return None
+        }
+
+        let rec = HitRecord {
+            t,
+            u: (x - self.x0) / (self.x1 - self.x0),
+            v: (z - self.z0) / (self.z1 - self.z0),
+            p: r.point_at_parameter(t),
+            normal: Vec3::unit_y(),
+            dpdu: Vec3::new(self.x1 - self.x0, 0.0, 0.0),
+            dpdv: Vec3::new(0.0, 0.0, self.z1 - self.z0),
+            color: None,
+            emission: None,
+            material: &self.material };
+
+        Some(rec)
+    }
+
+    fn bounding_box(&self, _t0: f32, _t1: f32) -> Option<AABB> {
+        let aabb = AABB::new(
+            Vec3::new(self.x0, self.k - RECT_PADDING, self.z0),
+            Vec3::new(self.x1, self.k + RECT_PADDING, self.z1),
+        );
+
+        Some(aabb)
+    }
+
+    fn clone_to_box(&self) -> Box<dyn Hitable> {
+        Box::new(self.clone())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct YZRect {
+    pub y0: f32,
+    pub y1: f32,
+    pub z0: f32,
+    pub z1: f32,
+    pub k: f32,
+    pub material: Material,
+}
+
+impl YZRect {
+    pub fn new(y0: f32, y1: f32, z0: f32, z1: f32, k: f32, mat: Material) -> Self {
+        YZRect { y0, y1, z0, z1, k, material: mat }
+    }
+}
+
+impl Hitable for YZRect {
+    fn hit(&self, r: Ray, t_min: f32, t_max: f32) -> Option<HitRecord<'_>> {
+        let t = (self.k - r.origin().x()) / r.direction().x();
+        if t < t_min || t > t_max {
+            return None
+        }
+
+        let y = r.origin().y() + t * r.direction().y();
+        let z = r.origin().z() + t * r.direction().z();
+        if y < self.y0 || y > self.y1 || z < self.z0 || z > self.z1 {
+            return None
+        }
+
+        let rec = HitRecord {
+            t,
+            u: (y - self.y0) / (self.y1 - self.y0),
+            v: (z - self.z0) / (self.z1 - self.z0),
+            p: r.point_at_parameter(t),
+            normal: Vec3::unit_x(),
+            dpdu: Vec3::new(0.0, self.y1 - self.y0, 0.0),
+            dpdv: Vec3::new(0.0, 0.0, self.z1 - self.z0),
+            color: None,
+            emission: None,
+            material: &self.material };
+
+        Some(rec)
+    }
+
+    fn bounding_box(&self, _t0: f32, _t1: f32) -> Option<AABB> {
+        let aabb = AABB::new(
+            Vec3::new(self.k - RECT_PADDING, self.y0, self.z0),
+            Vec3::new(self.k + RECT_PADDING, self.y1, self.z1),
+        );
+
+        Some(aabb)
+    }
+
+    fn clone_to_box(&self) -> Box<dyn Hitable> {
+        Box::new(self.clone())
+    }
+}
\ No newline at end of file
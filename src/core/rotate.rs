@@ -0,0 +1,145 @@
+use super::vec3::*;
+use super::ray::*;
+use super::hitable::*;
+use super::aabb::*;
+use super::transform::*;
+use std::f32;
+
+// Rotation by an angle in the plane of two coordinate axes, taking `a` towards `b`
+#[derive(Clone, Copy, Debug)]
+struct AxisRotation {
+    a: usize,
+    b: usize,
+    sin_theta: f32,
+    cos_theta: f32,
+    transform: Transform,
+}
+
+impl AxisRotation {
+    fn new(a: usize, b: usize, angle: f32) -> Self {
+        let radians = angle * f32::consts::PI / 180.0;
+        let mut axis = Vec3::zero();
+        axis[3 - a - b] = 1.0;
+        AxisRotation {
+            a,
+            b,
+            sin_theta: f32::sin(radians),
+            cos_theta: f32::cos(radians),
+            transform: Transform::rotate(axis, angle) }
+    }
+
+    fn rotate(&self, v: Vec3, sin_theta: f32) -> Vec3 {
+        let mut result = v;
+        result[self.a] = self.cos_theta * v[self.a] - sin_theta * v[self.b];
+        result[self.b] = sin_theta * v[self.a] + self.cos_theta * v[self.b];
+        result
+    }
+
+    fn object_to_world(&self, v: Vec3) -> Vec3 {
+        self.rotate(v, self.sin_theta)
+    }
+
+    fn world_to_object(&self, v: Vec3) -> Vec3 {
+        self.rotate(v, -self.sin_theta)
+    }
+
+    fn hit<'a>(&self, hitable: &'a dyn Hitable, r: Ray, t_min: f32, t_max: f32) -> Option<HitRecord<'a>> {
+        let rotated = Ray::new(self.world_to_object(r.origin()), self.world_to_object(r.direction()), r.time());
+
+        match hitable.hit(rotated, t_min, t_max) {
+            Some(mut rec) => {
+                rec.p = self.object_to_world(rec.p);
+                rec.normal = self.object_to_world(rec.normal);
+                rec.dpdu = self.object_to_world(rec.dpdu);
+                rec.dpdv = self.object_to_world(rec.dpdv);
+                Some(rec)
+            },
+            None => None
+        }
+    }
+
+    fn bounding_box(&self, hitable: &dyn Hitable, t0: f32, t1: f32) -> Option<AABB> {
+        hitable.bounding_box(t0, t1).map(|aabb| self.transform.transform_aabb(&aabb))
+    }
+}
+
+// Rotates the wrapped hitable about the x axis by an angle in degrees
+#[derive(Clone)]
+pub struct RotateX {
+    pub hitable: Box<dyn Hitable>,
+    rotation: AxisRotation,
+}
+
+impl RotateX {
+    pub fn new(hitable: Box<dyn Hitable>, angle: f32) -> Self {
+        RotateX { hitable, rotation: AxisRotation::new(1, 2, angle) }
+    }
+}
+
+impl Hitable for RotateX {
+    fn hit(&self, r: Ray, t_min: f32, t_max: f32) -> Option<HitRecord<'_>> {
+        self.rotation.hit(self.hitable.as_ref(), r, t_min, t_max)
+    }
+
+    fn bounding_box(&self, t0: f32, t1: f32) -> Option<AABB> {
+        self.rotation.bounding_box(self.hitable.as_ref(), t0, t1)
+    }
+
+    fn clone_to_box(&self) -> Box<dyn Hitable> {
+        Box::new(self.clone())
+    }
+}
+
+// Rotates the wrapped hitable about the y axis by an angle in degrees
+#[derive(Clone)]
+pub struct RotateY {
+    pub hitable: Box<dyn Hitable>,
+    rotation: AxisRotation,
+}
+
+impl RotateY {
+    pub fn new(hitable: Box<dyn Hitable>, angle: f32) -> Self {
+        RotateY { hitable, rotation: AxisRotation::new(2, 0, angle) }
+    }
+}
+
+impl Hitable for RotateY {
+    fn hit(&self, r: Ray, t_min: f32, t_max: f32) -> Option<HitRecord<'_>> {
+        self.rotation.hit(self.hitable.as_ref(), r, t_min, t_max)
+    }
+
+    fn bounding_box(&self, t0: f32, t1: f32) -> Option<AABB> {
+        self.rotation.bounding_box(self.hitable.as_ref(), t0, t1)
+    }
+
+    fn clone_to_box(&self) -> Box<dyn Hitable> {
+        Box::new(self.clone())
+    }
+}
+
+// Rotates the wrapped hitable about the z axis by an angle in degrees
+#[derive(Clone)]
+pub struct RotateZ {
+    pub hitable: Box<dyn Hitable>,
+    rotation: AxisRotation,
+}
+
+impl RotateZ {
+    pub fn new(hitable: Box<dyn Hitable>, angle: f32) -> Self {
+        RotateZ { hitable, rotation: AxisRotation::new(0, 1, angle) }
+    }
+}
+
+impl Hitable for RotateZ {
+    fn hit(&self, r: Ray, t_min: f32, t_max: f32) -> Option<HitRecord<'_>> {
+        self.rotation.hit(self.hitable.as_ref(), r, t_min, t_max)
+    }
+
+    fn bounding_box(&self, t0: f32, t1: f32) -> Option<AABB> {
+        self.rotation.bounding_box(self.hitable.as_ref(), t0, t1)
+    }
+
+    fn clone_to_box(&self) -> Box<dyn Hitable> {
+        Box::new(self.clone())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,255 @@
+use super::vec3::*;
+use super::ray::*;
+use super::hitable::*;
+use super::material::*;
+use super::aabb::*;
+use std::f32;
+use std::sync::Arc;
+
+// A signed distance field expression. Primitives and combinators can be nested freely;
+// Function wraps an arbitrary closure for fields that aren't expressible otherwise.
+#[derive(Clone)]
+pub enum Sdf {
+    Sphere { center: Vec3, radius: f32 },
+    Cuboid { center: Vec3, half_extents: Vec3 },
+    RoundCuboid { center: Vec3, half_extents: Vec3, radius: f32 },
+    Union(Box<Sdf>, Box<Sdf>),
+    Intersection(Box<Sdf>, Box<Sdf>),
+    Difference(Box<Sdf>, Box<Sdf>),
+    SmoothUnion(Box<Sdf>, Box<Sdf>, f32),
+    Translate(Box<Sdf>, Vec3),
+    Twist(Box<Sdf>, f32),
+    Repeat(Box<Sdf>, Vec3),
+    Function(Arc<dyn Fn(Vec3) -> f32 + Send + Sync>),
+}
+
+impl Sdf {
+    pub fn sphere(center: Vec3, radius: f32) -> Self {
+        Sdf::Sphere { center, radius }
+    }
+
+    pub fn cuboid(center: Vec3, half_extents: Vec3) -> Self {
+        Sdf::Cuboid { center, half_extents }
+    }
+
+    pub fn round_cuboid(center: Vec3, half_extents: Vec3, radius: f32) -> Self {
+        Sdf::RoundCuboid { center, half_extents, radius }
+    }
+
+    pub fn function<F: Fn(Vec3) -> f32 + Send + Sync + 'static>(f: F) -> Self {
+        Sdf::Function(Arc::new(f))
+    }
+
+    pub fn union(self, other: Sdf) -> Self {
+        Sdf::Union(Box::new(self), Box::new(other))
+    }
+
+    pub fn intersection(self, other: Sdf) -> Self {
+        Sdf::Intersection(Box::new(self), Box::new(other))
+    }
+
+    pub fn difference(self, other: Sdf) -> Self {
+        Sdf::Difference(Box::new(self), Box::new(other))
+    }
+
+    // Blends the two shapes over a distance of roughly k
+    pub fn smooth_union(self, other: Sdf, k: f32) -> Self {
+        Sdf::SmoothUnion(Box::new(self), Box::new(other), k)
+    }
+
+    pub fn translate(self, offset: Vec3) -> Self {
+        Sdf::Translate(Box::new(self), offset)
+    }
+
+    // Twists around the y axis by `rate` radians per unit of height. Twisting stretches
+    // distances, so pair it with SdfHitable::with_step_scale below 1.
+    pub fn twist(self, rate: f32) -> Self {
+        Sdf::Twist(Box::new(self), rate)
+    }
+
+    // Repeats the shape with the given period along each axis; a period of 0 disables
+    // repetition along that axis
+    pub fn repeat(self, period: Vec3) -> Self {
+        Sdf::Repeat(Box::new(self), period)
+    }
+
+    pub fn distance(&self, p: Vec3) -> f32 {
+        match *self {
+            Sdf::Sphere { center, radius } => (p - center).length() - radius,
+            Sdf::Cuboid { center, half_extents } => cuboid_distance(p - center, half_extents),
+            Sdf::RoundCuboid { center, half_extents, radius } => cuboid_distance(p - center, half_extents - radius) - radius,
+            Sdf::Union(ref a, ref b) => f32::min(a.distance(p), b.distance(p)),
+            Sdf::Intersection(ref a, ref b) => f32::max(a.distance(p), b.distance(p)),
+            Sdf::Difference(ref a, ref b) => f32::max(a.distance(p), -b.distance(p)),
+            Sdf::SmoothUnion(ref a, ref b, k) => {
+                let (da, db) = (a.distance(p), b.distance(p));
+                let h = (0.5 + 0.5 * (db - da) / k).clamp(0.0, 1.0);
+                db + (da - db) * h - k * h * (1.0 - h)
+            },
+            Sdf::Translate(ref a, offset) => a.distance(p - offset),
+            Sdf::Twist(ref a, rate) => {
+                let (s, c) = f32::sin_cos(rate * p.y());
+                a.distance(Vec3::new(c * p.x() - s * p.z(), p.y(), s * p.x() + c * p.z()))
+            },
+            Sdf::Repeat(ref a, period) => {
+                let mut q = p;
+                for i in 0..3 {
+                    if period[i] > 0.0 {
+                        q[i] = p[i] - period[i] * (p[i] / period[i]).round();
+                    }
+                }
+                a.distance(q)
+            },
+            Sdf::Function(ref f) => f(p),
+        }
+    }
+
+    // A box the zero level set is guaranteed to lie in, or None when it is unbounded
+    // (infinite repetition) or unknown (closures)
+    pub fn bounds(&self) -> Option<AABB> {
+        match *self {
+            Sdf::Sphere { center, radius } => Some(AABB::new(center - radius, center + radius)),
+            Sdf::Cuboid { center, half_extents } | Sdf::RoundCuboid { center, half_extents, .. } => {
+                Some(AABB::new(center - half_extents, center + half_extents))
+            },
+            Sdf::Union(ref a, ref b) => match (a.bounds(), b.bounds()) {
+                (Some(ref box_a), Some(ref box_b)) => Some(surrounding_box(box_a, box_b)),
+                _ => None
+            },
+            Sdf::Intersection(ref a, ref b) => a.bounds().or_else(|| b.bounds()),
+            Sdf::Difference(ref a, _) => a.bounds(),
+            Sdf::SmoothUnion(ref a, ref b, k) => match (a.bounds(), b.bounds()) {
+                (Some(ref box_a), Some(ref box_b)) => {
+                    let aabb = surrounding_box(box_a, box_b);
+                    Some(AABB::new(aabb.min() - k, aabb.max() + k))
+                },
+                _ => None
+            },
+            Sdf::Translate(ref a, offset) => a.bounds().map(|aabb| AABB::new(aabb.min() + offset, aabb.max() + offset)),
+            Sdf::Twist(ref a, _) => a.bounds().map(|aabb| {
+                let x = f32::max(aabb.min().x().abs(), aabb.max().x().abs());
+                let z = f32::max(aabb.min().z().abs(), aabb.max().z().abs());
+                let radius = f32::sqrt(x * x + z * z);
+                AABB::new(Vec3::new(-radius, aabb.min().y(), -radius), Vec3::new(radius, aabb.max().y(), radius))
+            }),
+            Sdf::Repeat(ref a, period) => {
+                if period.x() > 0.0 || period.y() > 0.0 || period.z() > 0.0 { None } else { a.bounds() }
+            },
+            Sdf::Function(_) => None,
+        }
+    }
+}
+
+fn cuboid_distance(p: Vec3, half_extents: Vec3) -> f32 {
+    let q = Vec3::new(p.x().abs(), p.y().abs(), p.z().abs()) - half_extents;
+    let outside = Vec3::new(f32::max(q.x(), 0.0), f32::max(q.y(), 0.0), f32::max(q.z(), 0.0)).length();
+    let inside = f32::min(f32::max(q.x(), f32::max(q.y(), q.z())), 0.0);
+    outside + inside
+}
+
+// Intersects an SDF by sphere tracing: stepping along the ray by the distance to the
+// nearest surface until that distance drops below `epsilon`.
+#[derive(Clone)]
+pub struct SdfHitable {
+    pub sdf: Sdf,
+    pub material: Material,
+    pub bounds: Option<AABB>,
+    pub max_steps: u32,
+    pub epsilon: f32,
+    pub step_scale: f32,
+}
+
+impl SdfHitable {
+    pub fn new(sdf: Sdf, mat: Material) -> Self {
+        let bounds = sdf.bounds();
+        SdfHitable { sdf, material: mat, bounds, max_steps: 256, epsilon: 0.0001, step_scale: 1.0 }
+    }
+
+    // Needed for closures and repetitions, whose bounds can't be derived
+    pub fn with_bounds(mut self, bounds: AABB) -> Self {
+        self.bounds = Some(bounds);
+        self
+    }
+
+    // Shortens every step, for fields that overestimate the true distance
+    pub fn with_step_scale(mut self, step_scale: f32) -> Self {
+        self.step_scale = step_scale;
+        self
+    }
+
+    pub fn with_max_steps(mut self, max_steps: u32) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    fn normal_at(&self, p: Vec3) -> Vec3 {
+        // Tetrahedral central differences: four evaluations instead of six
+        let h = 0.5 * self.epsilon;
+        let k0 = Vec3::new(1.0, -1.0, -1.0);
+        let k1 = Vec3::new(-1.0, -1.0, 1.0);
+        let k2 = Vec3::new(-1.0, 1.0, -1.0);
+        let k3 = Vec3::new(1.0, 1.0, 1.0);
+        let gradient = k0 * self.sdf.distance(p + h * k0)
+            + k1 * self.sdf.distance(p + h * k1)
+            + k2 * self.sdf.distance(p + h * k2)
+            + k3 * self.sdf.distance(p + h * k3);
+
+        if gradient.squared_length() > 0.0 { unit_vector(gradient) } else { Vec3::unit_y() }
+    }
+}
+
+impl Hitable for SdfHitable {
+    fn hit(&self, r: Ray, t_min: f32, t_max: f32) -> Option<HitRecord<'_>> {
+        let (t_start, t_end) = match self.bounds {
+            Some(ref aabb) => aabb.intersect(r, t_min, t_max)?,
+            None => (t_min, t_max)
+        };
+
+        let dir_length = r.direction().length();
+        let mut t = t_start;
+
+        // Rays that start inside the surface (e.g. refracted ones) march on the negated field
+        let sign = if self.sdf.distance(r.point_at_parameter(t)) < 0.0 { -1.0 } else { 1.0 };
+
+        for _ in 0..self.max_steps {
+            if t > t_end {
+                return None
+            }
+
+            let p = r.point_at_parameter(t);
+            let distance = sign * self.sdf.distance(p);
+            if distance < self.epsilon {
+                if t <= t_min {
+                    t += self.epsilon / dir_length;
+                    continue
+                }
+
+                let rec = HitRecord {
+                    t,
+                    u: 0.0,
+                    v: 0.0,
+                    p,
+                    normal: self.normal_at(p),
+                    dpdu: Vec3::zero(),
+                    dpdv: Vec3::zero(),
+                    color: None,
+                    emission: None,
+                    material: &self.material };
+
+                return Some(rec)
+            }
+
+            t += self.step_scale * distance / dir_length;
+        }
+
+        None
+    }
+
+    fn bounding_box(&self, _t0: f32, _t1: f32) -> Option<AABB> {
+        self.bounds.map(|aabb| AABB::new(aabb.min() - self.epsilon, aabb.max() + self.epsilon))
+    }
+
+    fn clone_to_box(&self) -> Box<dyn Hitable> {
+        Box::new(self.clone())
+    }
+}
\ No newline at end of file
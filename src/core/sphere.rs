@@ -5,7 +5,29 @@ use super::material::*;
 use super::aabb::*;
 use std::f32;
 
-#[derive(Clone, Copy, Debug)]
+// Maps a point on the unit sphere to texture coordinates: u runs once around the y axis
+// starting from -x, and v from the south pole to the north pole
+pub fn sphere_uv(p: Vec3) -> (f32, f32) {
+    let phi = f32::atan2(-p.z(), p.x()) + f32::consts::PI;
+    let theta = f32::acos((-p.y()).clamp(-1.0, 1.0));
+    (phi / (2.0 * f32::consts::PI), theta / f32::consts::PI)
+}
+
+// Derivatives of position with respect to the sphere_uv coordinates at `local`, a point
+// relative to the center. They vanish at the poles, where the mapping is singular.
+pub fn sphere_uv_derivatives(local: Vec3) -> (Vec3, Vec3) {
+    let rho = f32::sqrt(local.x() * local.x() + local.z() * local.z());
+    let dpdu = 2.0 * f32::consts::PI * Vec3::new(local.z(), 0.0, -local.x());
+    let dpdv = if rho > 0.0 {
+        f32::consts::PI * Vec3::new(-local.y() * local.x() / rho, rho, -local.y() * local.z() / rho)
+    }
+    else {
+        Vec3::zero()
+    };
+    (dpdu, dpdv)
+}
+
+#[derive(Clone, Debug)]
 pub struct Sphere {
     pub center: Vec3,
     pub radius: f32,
@@ -19,7 +41,7 @@ impl Sphere {
 }
 
 impl Hitable for Sphere {
-    fn hit(&self, r: Ray, t_min: f32, t_max: f32) -> Option<HitRecord> {
+    fn hit(&self, r: Ray, t_min: f32, t_max: f32) -> Option<HitRecord<'_>> {
         let oc = r.origin() - self.center;
         let a = dot(r.direction(), r.direction());
         let b = dot(oc, r.direction());
@@ -31,11 +53,21 @@ impl Hitable for Sphere {
             if (temp < t_max) && (temp > t_min) {
 
                 let pos = r.point_at_parameter(temp);
+                let normal = (pos - self.center) / self.radius;
+                // Hollow spheres have a negative radius, flipping the normal but not the mapping
+                let (u, v) = sphere_uv((pos - self.center) / self.radius.abs());
+                let (dpdu, dpdv) = sphere_uv_derivatives(pos - self.center);
                 let rec = HitRecord {
                     t: temp,
+                    u,
+                    v,
                     p: pos,
-                    normal: (pos - self.center) / self.radius,
-                    material: self.material };
+                    normal,
+                    dpdu,
+                    dpdv,
+                    color: None,
+                    emission: None,
+                    material: &self.material };
 
                 return Some(rec)
             }
@@ -43,11 +75,20 @@ impl Hitable for Sphere {
             if (temp < t_max) && (temp > t_min) {
 
                 let pos = r.point_at_parameter(temp);
+                let normal = (pos - self.center) / self.radius;
+                let (u, v) = sphere_uv((pos - self.center) / self.radius.abs());
+                let (dpdu, dpdv) = sphere_uv_derivatives(pos - self.center);
                 let rec = HitRecord {
                     t: temp,
+                    u,
+                    v,
                     p: pos,
-                    normal: (pos - self.center) / self.radius,
-                    material: self.material };
+                    normal,
+                    dpdu,
+                    dpdv,
+                    color: None,
+                    emission: None,
+                    material: &self.material };
 
                 return Some(rec)
             }
@@ -66,11 +107,11 @@ impl Hitable for Sphere {
     }
 
     fn clone_to_box(&self) -> Box<dyn Hitable> {
-        Box::new(*self)
+        Box::new(self.clone())
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 pub struct MovableSphere {
     pub center0: Vec3,
     pub center1: Vec3,
@@ -97,7 +138,7 @@ impl MovableSphere {
 }
 
 impl Hitable for MovableSphere {
-    fn hit(&self, r: Ray, t_min: f32, t_max: f32) -> Option<HitRecord> {
+    fn hit(&self, r: Ray, t_min: f32, t_max: f32) -> Option<HitRecord<'_>> {
         let oc = r.origin() - self.center(r.time());
         let a = dot(r.direction(), r.direction());
         let b = dot(oc, r.direction());
@@ -109,11 +150,20 @@ impl Hitable for MovableSphere {
             if (temp < t_max) && (temp > t_min) {
 
                 let pos = r.point_at_parameter(temp);
+                let center = self.center(r.time());
+                let (u, v) = sphere_uv((pos - center) / self.radius.abs());
+                let (dpdu, dpdv) = sphere_uv_derivatives(pos - center);
                 let rec = HitRecord {
                     t: temp,
+                    u,
+                    v,
                     p: pos,
-                    normal: (pos - self.center(r.time())) / self.radius,
-                    material: self.material };
+                    normal: (pos - center) / self.radius,
+                    dpdu,
+                    dpdv,
+                    color: None,
+                    emission: None,
+                    material: &self.material };
 
                 return Some(rec)
             }
@@ -121,11 +171,20 @@ impl Hitable for MovableSphere {
             if (temp < t_max) && (temp > t_min) {
 
                 let pos = r.point_at_parameter(temp);
+                let center = self.center(r.time());
+                let (u, v) = sphere_uv((pos - center) / self.radius.abs());
+                let (dpdu, dpdv) = sphere_uv_derivatives(pos - center);
                 let rec = HitRecord {
                     t: temp,
+                    u,
+                    v,
                     p: pos,
-                    normal: (pos - self.center(r.time())) / self.radius,
-                    material: self.material };
+                    normal: (pos - center) / self.radius,
+                    dpdu,
+                    dpdv,
+                    color: None,
+                    emission: None,
+                    material: &self.material };
 
                 return Some(rec)
             }
@@ -149,6 +208,6 @@ impl Hitable for MovableSphere {
     }
 
     fn clone_to_box(&self) -> Box<dyn Hitable> {
-        Box::new(*self)
+        Box::new(self.clone())
     }
 }
\ No newline at end of file
@@ -0,0 +1,168 @@
+use super::vec3::*;
+use super::perlin::*;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+// How far the texture coordinates move across one pixel, in x and y
+#[derive(Clone, Copy, Debug)]
+pub struct TextureFootprint {
+    pub dudx: f32,
+    pub dvdx: f32,
+    pub dudy: f32,
+    pub dvdy: f32,
+}
+
+// A colour that varies over a surface, looked up by texture coordinates and hit point
+pub trait Texture: Debug + Send + Sync {
+    fn value(&self, u: f32, v: f32, p: &Vec3) -> Vec3;
+
+    // The average over a pixel's footprint, for textures that can prefilter; others just
+    // return the point value
+    fn filtered_value(&self, u: f32, v: f32, p: &Vec3, _footprint: &TextureFootprint) -> Vec3 {
+        self.value(u, v, p)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct SolidColor {
+    pub color: Vec3,
+}
+
+impl SolidColor {
+    pub fn new(color: Vec3) -> Self {
+        SolidColor { color }
+    }
+}
+
+impl Texture for SolidColor {
+    fn value(&self, _u: f32, _v: f32, _p: &Vec3) -> Vec3 {
+        self.color
+    }
+}
+
+// Alternates between two textures in a 3D grid of cubes `1 / frequency` wide, so it
+// needs no texture coordinates and can't stretch across a surface
+#[derive(Clone, Debug)]
+pub struct Checker {
+    pub even: Arc<dyn Texture>,
+    pub odd: Arc<dyn Texture>,
+    pub frequency: f32,
+}
+
+impl Checker {
+    pub fn new(even: Arc<dyn Texture>, odd: Arc<dyn Texture>, frequency: f32) -> Self {
+        Checker { even, odd, frequency }
+    }
+
+    pub fn from_colors(even: Vec3, odd: Vec3, frequency: f32) -> Self {
+        Checker::new(Arc::new(SolidColor::new(even)), Arc::new(SolidColor::new(odd)), frequency)
+    }
+}
+
+impl Checker {
+    fn pick(&self, p: &Vec3) -> &Arc<dyn Texture> {
+        let cell = (self.frequency * p.x()).floor() + (self.frequency * p.y()).floor() + (self.frequency * p.z()).floor();
+        if cell.rem_euclid(2.0) < 1.0 { &self.even } else { &self.odd }
+    }
+}
+
+impl Texture for Checker {
+    fn value(&self, u: f32, v: f32, p: &Vec3) -> Vec3 {
+        self.pick(p).value(u, v, p)
+    }
+
+    fn filtered_value(&self, u: f32, v: f32, p: &Vec3, footprint: &TextureFootprint) -> Vec3 {
+        self.pick(p).filtered_value(u, v, p, footprint)
+    }
+}
+
+// Perlin noise remapped to [0, 1], or turbulence when `turbulence_depth` is set, which
+// reads as clouds or smoke rather than smooth blobs
+#[derive(Clone, Debug)]
+pub struct NoiseTexture {
+    pub noise: Arc<Perlin>,
+    pub scale: f32,
+    pub color: Vec3,
+    pub turbulence_depth: Option<u32>,
+}
+
+impl NoiseTexture {
+    pub fn new(scale: f32) -> Self {
+        NoiseTexture { noise: Arc::new(Perlin::new()), scale, color: Vec3::one(), turbulence_depth: None }
+    }
+
+    pub fn with_noise(mut self, noise: Arc<Perlin>) -> Self {
+        self.noise = noise;
+        self
+    }
+
+    pub fn with_color(mut self, color: Vec3) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn with_turbulence(mut self, depth: u32) -> Self {
+        self.turbulence_depth = Some(depth);
+        self
+    }
+}
+
+impl Texture for NoiseTexture {
+    fn value(&self, _u: f32, _v: f32, p: &Vec3) -> Vec3 {
+        let point = self.scale * *p;
+        let intensity = match self.turbulence_depth {
+            Some(depth) => self.noise.turbulence(&point, depth),
+            None => 0.5 * (1.0 + self.noise.noise(&point)),
+        };
+        intensity.clamp(0.0, 1.0) * self.color
+    }
+}
+
+// Sinusoidal bands along z whose phase is disturbed by turbulence, giving marble veins.
+// `distortion` sets how far the veins wander from straight stripes.
+#[derive(Clone, Debug)]
+pub struct MarbleTexture {
+    pub noise: Arc<Perlin>,
+    pub scale: f32,
+    pub distortion: f32,
+    pub depth: u32,
+    pub vein: Vec3,
+    pub base: Vec3,
+}
+
+impl MarbleTexture {
+    pub fn new(scale: f32) -> Self {
+        MarbleTexture {
+            noise: Arc::new(Perlin::new()),
+            scale,
+            distortion: 10.0,
+            depth: 7,
+            vein: Vec3::zero(),
+            base: Vec3::one() }
+    }
+
+    pub fn with_noise(mut self, noise: Arc<Perlin>) -> Self {
+        self.noise = noise;
+        self
+    }
+
+    pub fn with_distortion(mut self, distortion: f32, depth: u32) -> Self {
+        self.distortion = distortion;
+        self.depth = depth;
+        self
+    }
+
+    pub fn with_colors(mut self, vein: Vec3, base: Vec3) -> Self {
+        self.vein = vein;
+        self.base = base;
+        self
+    }
+}
+
+impl Texture for MarbleTexture {
+    fn value(&self, _u: f32, _v: f32, p: &Vec3) -> Vec3 {
+        let phase = self.scale * p.z() + self.distortion * self.noise.turbulence(p, self.depth);
+        let t = 0.5 * (1.0 + f32::sin(phase));
+        self.vein + t * (self.base - self.vein)
+    }
+}
\ No newline at end of file
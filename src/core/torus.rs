@@ -0,0 +1,154 @@
+use super::vec3::*;
+use super::ray::*;
+use super::hitable::*;
+use super::material::*;
+use super::polynomial::*;
+use super::aabb::*;
+use std::f32;
+
+// A torus around the y axis through `center`. The tube of radius `minor_radius` is swept
+// along a circle of radius `major_radius` in the xz plane.
+#[derive(Clone, Debug)]
+pub struct Torus {
+    pub center: Vec3,
+    pub major_radius: f32,
+    pub minor_radius: f32,
+    pub material: Material,
+}
+
+impl Torus {
+    pub fn new(center: Vec3, major_radius: f32, minor_radius: f32, mat: Material) -> Self {
+        Torus { center, major_radius, minor_radius, material: mat }
+    }
+
+    fn normal_at(&self, p: Vec3) -> Vec3 {
+        let big_r2 = self.major_radius * self.major_radius;
+        let alpha = p.squared_length() + big_r2 - self.minor_radius * self.minor_radius;
+        unit_vector(Vec3::new(p.x() * (alpha - 2.0 * big_r2), p.y() * alpha, p.z() * (alpha - 2.0 * big_r2)))
+    }
+}
+
+impl Hitable for Torus {
+    fn hit(&self, r: Ray, t_min: f32, t_max: f32) -> Option<HitRecord<'_>> {
+        // Work in f64 with a unit direction, and restart the ray at its closest approach to
+        // the center so the quartic's coefficients stay small for distant origins.
+        let dir = r.direction();
+        let dir_length = f64::from(dir.length());
+        let d = [f64::from(dir.x()) / dir_length, f64::from(dir.y()) / dir_length, f64::from(dir.z()) / dir_length];
+        let oc = r.origin() - self.center;
+        let mut o = [f64::from(oc.x()), f64::from(oc.y()), f64::from(oc.z())];
+
+        let t_shift = -(o[0] * d[0] + o[1] * d[1] + o[2] * d[2]);
+        for a in 0..3 {
+            o[a] += t_shift * d[a];
+        }
+
+        let big_r2 = f64::from(self.major_radius) * f64::from(self.major_radius);
+        let small_r2 = f64::from(self.minor_radius) * f64::from(self.minor_radius);
+
+        // Early out against the bounding sphere
+        let bound = f64::from(self.major_radius + self.minor_radius);
+        let oo = o[0] * o[0] + o[1] * o[1] + o[2] * o[2];
+        if oo > bound * bound {
+            return None
+        }
+
+        let od = o[0] * d[0] + o[1] * d[1] + o[2] * d[2];
+        let e = oo + big_r2 - small_r2;
+        let dxz = d[0] * d[0] + d[2] * d[2];
+        let oxz = o[0] * d[0] + o[2] * d[2];
+        let ooxz = o[0] * o[0] + o[2] * o[2];
+
+        let roots = solve_quartic(
+            4.0 * od,
+            4.0 * od * od + 2.0 * e - 4.0 * big_r2 * dxz,
+            4.0 * od * e - 8.0 * big_r2 * oxz,
+            e * e - 4.0 * big_r2 * ooxz);
+
+        for root in roots {
+            let t = ((root + t_shift) / dir_length) as f32;
+            if t <= t_min || t >= t_max {
+                continue
+            }
+
+            let p = r.point_at_parameter(t);
+            let local = p - self.center;
+            let phi = f32::atan2(local.z(), local.x());
+            let ring = f32::sqrt(local.x() * local.x() + local.z() * local.z()) - self.major_radius;
+            let theta = f32::atan2(local.y(), ring);
+            let two_pi = 2.0 * f32::consts::PI;
+
+            // Around the tube the point moves by (-y cos(phi), ring, -y sin(phi)) per radian
+            let rho = ring + self.major_radius;
+            let (cos_phi, sin_phi) = if rho > 0.0 { (local.x() / rho, local.z() / rho) } else { (1.0, 0.0) };
+
+            let rec = HitRecord {
+                t,
+                u: if phi < 0.0 { phi + two_pi } else { phi } / two_pi,
+                v: if theta < 0.0 { theta + two_pi } else { theta } / two_pi,
+                p,
+                normal: self.normal_at(local),
+                dpdu: two_pi * Vec3::new(-local.z(), 0.0, local.x()),
+                dpdv: two_pi * Vec3::new(-local.y() * cos_phi, ring, -local.y() * sin_phi),
+                color: None,
+                emission: None,
+                material: &self.material };
+
+            return Some(rec)
+        }
+
+        None
+    }
+
+    fn bounding_box(&self, _t0: f32, _t1: f32) -> Option<AABB> {
+        let extent = Vec3::new(self.major_radius + self.minor_radius, self.minor_radius, self.major_radius + self.minor_radius);
+        Some(AABB::new(self.center - extent, self.center + extent))
+    }
+
+    fn clone_to_box(&self) -> Box<dyn Hitable> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn torus() -> Torus {
+        Torus::new(Vec3::new(1.0, -2.0, 0.5), 2.0, 0.5, Material::lambertian(Vec3::new(0.5, 0.5, 0.5)))
+    }
+
+    fn ray(origin: Vec3, direction: Vec3) -> Ray {
+        Ray::new(torus().center + origin, direction, 0.0)
+    }
+
+    #[test]
+    fn ray_through_the_hole_misses() {
+        let torus = torus();
+        assert!(torus.hit(ray(Vec3::new(0.0, 10.0, 0.0), Vec3::new(0.0, -1.0, 0.0)), 0.001, f32::MAX).is_none());
+        assert!(torus.hit(ray(Vec3::new(0.3, 10.0, -0.4), Vec3::new(0.0, -3.0, 0.0)), 0.001, f32::MAX).is_none());
+    }
+
+    #[test]
+    fn ray_across_the_hole_hits_the_near_and_far_tube() {
+        let torus = torus();
+        let hit = torus.hit(ray(Vec3::new(10.0, 0.0, 0.0), Vec3::new(-2.0, 0.0, 0.0)), 0.001, f32::MAX).unwrap();
+        assert!((hit.t - 3.75).abs() < 1e-4, "t = {}", hit.t);
+        assert!((hit.normal.x() - 1.0).abs() < 1e-4, "{:?}", hit.normal);
+
+        // Starting inside the hole, the next surface is the inner side of the far tube
+        let hit = torus.hit(ray(Vec3::zero(), Vec3::new(-1.0, 0.0, 0.0)), 0.001, f32::MAX).unwrap();
+        assert!((hit.t - 1.5).abs() < 1e-4, "t = {}", hit.t);
+        assert!((hit.normal.x() - 1.0).abs() < 1e-4, "{:?}", hit.normal);
+    }
+
+    #[test]
+    fn ray_tangent_to_the_tube() {
+        let torus = torus();
+        let hit = torus.hit(ray(Vec3::new(10.0, 0.5, 0.0), Vec3::new(-1.0, 0.0, 0.0)), 0.001, f32::MAX).unwrap();
+        assert!((hit.t - 8.0).abs() < 1e-2, "t = {}", hit.t);
+        assert!(hit.normal.y() > 0.999, "{:?}", hit.normal);
+
+        assert!(torus.hit(ray(Vec3::new(10.0, 0.501, 0.0), Vec3::new(-1.0, 0.0, 0.0)), 0.001, f32::MAX).is_none());
+    }
+}
\ No newline at end of file
@@ -0,0 +1,249 @@
+use super::vec3::*;
+use super::ray::*;
+use super::aabb::*;
+use super::quaternion::*;
+use std::ops::Mul;
+use std::f32;
+
+// Row-major 4x4 matrix, applied to column vectors
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Mat4 {
+    pub m: [[f32; 4]; 4],
+}
+
+impl Mat4 {
+    pub fn new(m: [[f32; 4]; 4]) -> Self {
+        Mat4 { m }
+    }
+
+    pub fn identity() -> Self {
+        Mat4 { m: [
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0] ] }
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut result = Mat4::identity();
+        for i in 0..4 {
+            for j in 0..4 {
+                result.m[i][j] = self.m[j][i];
+            }
+        }
+        result
+    }
+
+    // Gauss-Jordan elimination with partial pivoting. Returns None for singular matrices.
+    pub fn inverse(&self) -> Option<Self> {
+        let mut a = self.m;
+        let mut inv = Mat4::identity().m;
+
+        for col in 0..4 {
+            let mut pivot = col;
+            for row in col + 1..4 {
+                if a[row][col].abs() > a[pivot][col].abs() {
+                    pivot = row;
+                }
+            }
+            if a[pivot][col].abs() < 1e-12 {
+                return None
+            }
+            a.swap(col, pivot);
+            inv.swap(col, pivot);
+
+            let scale = 1.0 / a[col][col];
+            for j in 0..4 {
+                a[col][j] *= scale;
+                inv[col][j] *= scale;
+            }
+
+            for row in 0..4 {
+                if row != col {
+                    let factor = a[row][col];
+                    for j in 0..4 {
+                        a[row][j] -= factor * a[col][j];
+                        inv[row][j] -= factor * inv[col][j];
+                    }
+                }
+            }
+        }
+
+        Some(Mat4 { m: inv })
+    }
+}
+
+impl Mul for Mat4 {
+    type Output = Mat4;
+
+    fn mul(self, rhs: Self) -> Self {
+        let mut result = Mat4 { m: [[0.0; 4]; 4] };
+        for i in 0..4 {
+            for j in 0..4 {
+                for k in 0..4 {
+                    result.m[i][j] += self.m[i][k] * rhs.m[k][j];
+                }
+            }
+        }
+        result
+    }
+}
+
+// An affine transform together with its inverse, so neither has to be recomputed per ray
+#[derive(Clone, Copy, Debug)]
+pub struct Transform {
+    m: Mat4,
+    m_inv: Mat4,
+}
+
+impl Transform {
+    pub fn from_matrix(m: Mat4) -> Option<Self> {
+        m.inverse().map(|m_inv| Transform { m, m_inv })
+    }
+
+    pub fn identity() -> Self {
+        Transform { m: Mat4::identity(), m_inv: Mat4::identity() }
+    }
+
+    pub fn translate(delta: Vec3) -> Self {
+        let m = Mat4::new([
+            [1.0, 0.0, 0.0, delta.x()],
+            [0.0, 1.0, 0.0, delta.y()],
+            [0.0, 0.0, 1.0, delta.z()],
+            [0.0, 0.0, 0.0, 1.0] ]);
+        let m_inv = Mat4::new([
+            [1.0, 0.0, 0.0, -delta.x()],
+            [0.0, 1.0, 0.0, -delta.y()],
+            [0.0, 0.0, 1.0, -delta.z()],
+            [0.0, 0.0, 0.0, 1.0] ]);
+        Transform { m, m_inv }
+    }
+
+    // Panics on a zero factor, which would collapse space and leave no inverse
+    pub fn scale(s: Vec3) -> Self {
+        assert!(s.x() != 0.0 && s.y() != 0.0 && s.z() != 0.0, "scale factors must be non-zero");
+        let m = Mat4::new([
+            [s.x(), 0.0, 0.0, 0.0],
+            [0.0, s.y(), 0.0, 0.0],
+            [0.0, 0.0, s.z(), 0.0],
+            [0.0, 0.0, 0.0, 1.0] ]);
+        let m_inv = Mat4::new([
+            [1.0 / s.x(), 0.0, 0.0, 0.0],
+            [0.0, 1.0 / s.y(), 0.0, 0.0],
+            [0.0, 0.0, 1.0 / s.z(), 0.0],
+            [0.0, 0.0, 0.0, 1.0] ]);
+        Transform { m, m_inv }
+    }
+
+    // Rotation by an angle in degrees about an arbitrary axis
+    pub fn rotate(axis: Vec3, angle: f32) -> Self {
+        let a = unit_vector(axis);
+        let radians = angle * f32::consts::PI / 180.0;
+        let (sin_theta, cos_theta) = (f32::sin(radians), f32::cos(radians));
+
+        let m = Mat4::new([
+            [a.x() * a.x() + (1.0 - a.x() * a.x()) * cos_theta,
+             a.x() * a.y() * (1.0 - cos_theta) - a.z() * sin_theta,
+             a.x() * a.z() * (1.0 - cos_theta) + a.y() * sin_theta,
+             0.0],
+            [a.x() * a.y() * (1.0 - cos_theta) + a.z() * sin_theta,
+             a.y() * a.y() + (1.0 - a.y() * a.y()) * cos_theta,
+             a.y() * a.z() * (1.0 - cos_theta) - a.x() * sin_theta,
+             0.0],
+            [a.x() * a.z() * (1.0 - cos_theta) - a.y() * sin_theta,
+             a.y() * a.z() * (1.0 - cos_theta) + a.x() * sin_theta,
+             a.z() * a.z() + (1.0 - a.z() * a.z()) * cos_theta,
+             0.0],
+            [0.0, 0.0, 0.0, 1.0] ]);
+
+        // Rotations are orthogonal, so the inverse is the transpose
+        Transform { m, m_inv: m.transpose() }
+    }
+
+    pub fn rotate_x(angle: f32) -> Self {
+        Transform::rotate(Vec3::unit_x(), angle)
+    }
+
+    pub fn rotate_y(angle: f32) -> Self {
+        Transform::rotate(Vec3::unit_y(), angle)
+    }
+
+    pub fn rotate_z(angle: f32) -> Self {
+        Transform::rotate(Vec3::unit_z(), angle)
+    }
+
+    pub fn from_quaternion(q: &Quaternion) -> Self {
+        let m = q.normalized().to_matrix();
+        Transform { m, m_inv: m.transpose() }
+    }
+
+    pub fn matrix(&self) -> Mat4 {
+        self.m
+    }
+
+    pub fn inverse_matrix(&self) -> Mat4 {
+        self.m_inv
+    }
+
+    pub fn inverse(&self) -> Self {
+        Transform { m: self.m_inv, m_inv: self.m }
+    }
+
+    pub fn transpose(&self) -> Self {
+        Transform { m: self.m.transpose(), m_inv: self.m_inv.transpose() }
+    }
+
+    pub fn transform_point(&self, p: Vec3) -> Vec3 {
+        let m = &self.m.m;
+        Vec3::new(
+            m[0][0] * p.x() + m[0][1] * p.y() + m[0][2] * p.z() + m[0][3],
+            m[1][0] * p.x() + m[1][1] * p.y() + m[1][2] * p.z() + m[1][3],
+            m[2][0] * p.x() + m[2][1] * p.y() + m[2][2] * p.z() + m[2][3])
+    }
+
+    pub fn transform_vector(&self, v: Vec3) -> Vec3 {
+        let m = &self.m.m;
+        Vec3::new(
+            m[0][0] * v.x() + m[0][1] * v.y() + m[0][2] * v.z(),
+            m[1][0] * v.x() + m[1][1] * v.y() + m[1][2] * v.z(),
+            m[2][0] * v.x() + m[2][1] * v.y() + m[2][2] * v.z())
+    }
+
+    // Normals transform by the inverse transpose. The result is not normalized.
+    pub fn transform_normal(&self, n: Vec3) -> Vec3 {
+        let m = &self.m_inv.m;
+        Vec3::new(
+            m[0][0] * n.x() + m[1][0] * n.y() + m[2][0] * n.z(),
+            m[0][1] * n.x() + m[1][1] * n.y() + m[2][1] * n.z(),
+            m[0][2] * n.x() + m[1][2] * n.y() + m[2][2] * n.z())
+    }
+
+    // The direction is left unnormalized so ray parameters stay valid in both spaces
+    pub fn transform_ray(&self, r: &Ray) -> Ray {
+        Ray::new(self.transform_point(r.origin()), self.transform_vector(r.direction()), r.time())
+    }
+
+    pub fn transform_aabb(&self, aabb: &AABB) -> AABB {
+        let mut small = Vec3::new(f32::MAX, f32::MAX, f32::MAX);
+        let mut big = Vec3::new(-f32::MAX, -f32::MAX, -f32::MAX);
+
+        for &corner in aabb.corners().iter() {
+            let p = self.transform_point(corner);
+            for a in 0..3 {
+                small[a] = f32::min(small[a], p[a]);
+                big[a] = f32::max(big[a], p[a]);
+            }
+        }
+
+        AABB::new(small, big)
+    }
+}
+
+// Composition: (a * b) applies b first, then a
+impl Mul for Transform {
+    type Output = Transform;
+
+    fn mul(self, rhs: Self) -> Self {
+        Transform { m: self.m * rhs.m, m_inv: rhs.m_inv * self.m_inv }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,47 @@
+use super::vec3::*;
+use super::ray::*;
+use super::hitable::*;
+use super::transform::*;
+use super::aabb::*;
+
+// Intersects a hitable defined in object space, where `transform` maps object space to world space
+pub fn hit_transformed<'a>(hitable: &'a dyn Hitable, transform: &Transform, r: Ray, t_min: f32, t_max: f32) -> Option<HitRecord<'a>> {
+    let object_ray = transform.inverse().transform_ray(&r);
+
+    match hitable.hit(object_ray, t_min, t_max) {
+        Some(mut rec) => {
+            rec.p = transform.transform_point(rec.p);
+            rec.normal = unit_vector(transform.transform_normal(rec.normal));
+            rec.dpdu = transform.transform_vector(rec.dpdu);
+            rec.dpdv = transform.transform_vector(rec.dpdv);
+            Some(rec)
+        },
+        None => None
+    }
+}
+
+#[derive(Clone)]
+pub struct Transformed {
+    pub hitable: Box<dyn Hitable>,
+    pub transform: Transform,
+}
+
+impl Transformed {
+    pub fn new(hitable: Box<dyn Hitable>, transform: Transform) -> Self {
+        Transformed { hitable, transform }
+    }
+}
+
+impl Hitable for Transformed {
+    fn hit(&self, r: Ray, t_min: f32, t_max: f32) -> Option<HitRecord<'_>> {
+        hit_transformed(self.hitable.as_ref(), &self.transform, r, t_min, t_max)
+    }
+
+    fn bounding_box(&self, t0: f32, t1: f32) -> Option<AABB> {
+        self.hitable.bounding_box(t0, t1).map(|aabb| self.transform.transform_aabb(&aabb))
+    }
+
+    fn clone_to_box(&self) -> Box<dyn Hitable> {
+        Box::new(self.clone())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,39 @@
+use super::vec3::*;
+use super::ray::*;
+use super::hitable::*;
+use super::aabb::*;
+
+#[derive(Clone)]
+pub struct Translate {
+    pub hitable: Box<dyn Hitable>,
+    pub offset: Vec3,
+}
+
+impl Translate {
+    pub fn new(hitable: Box<dyn Hitable>, offset: Vec3) -> Self {
+        Translate { hitable, offset }
+    }
+}
+
+impl Hitable for Translate {
+    fn hit(&self, r: Ray, t_min: f32, t_max: f32) -> Option<HitRecord<'_>> {
+        let moved = Ray::new(r.origin() - self.offset, r.direction(), r.time());
+
+        match self.hitable.hit(moved, t_min, t_max) {
+            Some(mut rec) => {
+                rec.p += self.offset;
+                Some(rec)
+            },
+            None => None
+        }
+    }
+
+    fn bounding_box(&self, t0: f32, t1: f32) -> Option<AABB> {
+        self.hitable.bounding_box(t0, t1)
+            .map(|aabb| AABB::new(aabb.min() + self.offset, aabb.max() + self.offset))
+    }
+
+    fn clone_to_box(&self) -> Box<dyn Hitable> {
+        Box::new(self.clone())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,145 @@
+use super::vec3::*;
+use super::ray::*;
+use super::hitable::*;
+use super::material::*;
+use super::aabb::*;
+use std::f32;
+
+// Minimum half-thickness of a triangle's bounding box, for triangles lying in an axis plane
+const TRIANGLE_PADDING: f32 = 0.0001;
+
+#[derive(Clone, Debug)]
+pub struct Triangle {
+    pub vertices: [Vec3; 3],
+    pub normals: Option<[Vec3; 3]>,
+    pub uvs: [(f32, f32); 3],
+    pub material: Material,
+}
+
+impl Triangle {
+    pub fn new(p0: Vec3, p1: Vec3, p2: Vec3, mat: Material) -> Self {
+        Triangle {
+            vertices: [p0, p1, p2],
+            normals: None,
+            uvs: [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)],
+            material: mat }
+    }
+
+    pub fn with_normals(mut self, n0: Vec3, n1: Vec3, n2: Vec3) -> Self {
+        self.normals = Some([n0, n1, n2]);
+        self
+    }
+
+    pub fn with_uvs(mut self, uv0: (f32, f32), uv1: (f32, f32), uv2: (f32, f32)) -> Self {
+        self.uvs = [uv0, uv1, uv2];
+        self
+    }
+
+    pub fn geometric_normal(&self) -> Vec3 {
+        unit_vector(cross(self.vertices[1] - self.vertices[0], self.vertices[2] - self.vertices[0]))
+    }
+}
+
+// Möller–Trumbore intersection. Returns the ray parameter and the barycentric
+// coordinates (b1, b2) of the hit with respect to p1 and p2.
+pub fn intersect_triangle(p0: Vec3, p1: Vec3, p2: Vec3, r: &Ray, t_min: f32, t_max: f32) -> Option<(f32, f32, f32)> {
+    let edge1 = p1 - p0;
+    let edge2 = p2 - p0;
+    let pvec = cross(r.direction(), edge2);
+    let det = dot(edge1, pvec);
+
+    // Parallel test relative to the size of the triangle and the ray direction, so that
+    // small or finely tessellated geometry isn't rejected along with grazing rays
+    if det.abs() <= f32::EPSILON * edge1.length() * edge2.length() * r.direction().length() {
+        return None
+    }
+
+    let inv_det = 1.0 / det;
+    let tvec = r.origin() - p0;
+    let b1 = dot(tvec, pvec) * inv_det;
+    if !(0.0..=1.0).contains(&b1) {
+        return None
+    }
+
+    let qvec = cross(tvec, edge1);
+    let b2 = dot(r.direction(), qvec) * inv_det;
+    if b2 < 0.0 || b1 + b2 > 1.0 {
+        return None
+    }
+
+    let t = dot(edge2, qvec) * inv_det;
+    if t < t_max && t > t_min {
+        Some((t, b1, b2))
+    }
+    else {
+        None
+    }
+}
+
+// Derivatives of position with respect to the texture coordinates, or zero when the UVs
+// are degenerate
+pub fn triangle_uv_derivatives(p: [Vec3; 3], uv: [(f32, f32); 3]) -> (Vec3, Vec3) {
+    let (du02, dv02) = (uv[0].0 - uv[2].0, uv[0].1 - uv[2].1);
+    let (du12, dv12) = (uv[1].0 - uv[2].0, uv[1].1 - uv[2].1);
+    let (dp02, dp12) = (p[0] - p[2], p[1] - p[2]);
+    let det = du02 * dv12 - dv02 * du12;
+    if det.abs() < 1e-12 {
+        return (Vec3::zero(), Vec3::zero())
+    }
+
+    let inv_det = 1.0 / det;
+    ((dv12 * dp02 - dv02 * dp12) * inv_det, (du02 * dp12 - du12 * dp02) * inv_det)
+}
+
+pub fn triangle_bounding_box(p0: Vec3, p1: Vec3, p2: Vec3) -> AABB {
+    let mut small = Vec3::zero();
+    let mut big = Vec3::zero();
+
+    for a in 0..3 {
+        small[a] = f32::min(p0[a], f32::min(p1[a], p2[a]));
+        big[a] = f32::max(p0[a], f32::max(p1[a], p2[a]));
+        if big[a] - small[a] < 2.0 * TRIANGLE_PADDING {
+            small[a] -= TRIANGLE_PADDING;
+            big[a] += TRIANGLE_PADDING;
+        }
+    }
+
+    AABB::new(small, big)
+}
+
+impl Hitable for Triangle {
+    fn hit(&self, r: Ray, t_min: f32, t_max: f32) -> Option<HitRecord<'_>> {
+        let [p0, p1, p2] = self.vertices;
+        let (t, b1, b2) = intersect_triangle(p0, p1, p2, &r, t_min, t_max)?;
+        let b0 = 1.0 - b1 - b2;
+
+        let normal = match self.normals {
+            Some([n0, n1, n2]) => unit_vector(b0 * n0 + b1 * n1 + b2 * n2),
+            None => self.geometric_normal()
+        };
+
+        let [uv0, uv1, uv2] = self.uvs;
+        let (dpdu, dpdv) = triangle_uv_derivatives(self.vertices, self.uvs);
+        let rec = HitRecord {
+            t,
+            u: b0 * uv0.0 + b1 * uv1.0 + b2 * uv2.0,
+            v: b0 * uv0.1 + b1 * uv1.1 + b2 * uv2.1,
+            p: r.point_at_parameter(t),
+            normal,
+            dpdu,
+            dpdv,
+            color: None,
+            emission: None,
+            material: &self.material };
+
+        Some(rec)
+    }
+
+    fn bounding_box(&self, _t0: f32, _t1: f32) -> Option<AABB> {
+        Some(triangle_bounding_box(self.vertices[0], self.vertices[1], self.vertices[2]))
+    }
+
+    fn clone_to_box(&self) -> Box<dyn Hitable> {
+        Box::new(self.clone())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,239 @@
+use super::vec3::*;
+use super::ray::*;
+use super::hitable::*;
+use super::material::*;
+use super::triangle::*;
+use super::aabb::*;
+use std::sync::Arc;
+
+const MAX_TRIANGLES_PER_LEAF: usize = 4;
+
+// Vertex and index buffers of a mesh. Normals, UVs and colours, when present, are
+// indexed the same way as the positions.
+#[derive(Clone, Debug)]
+pub struct MeshData {
+    pub positions: Vec<Vec3>,
+    pub normals: Option<Vec<Vec3>>,
+    pub uvs: Option<Vec<(f32, f32)>>,
+    pub colors: Option<Vec<Vec3>>,
+    pub indices: Vec<[u32; 3]>,
+    pub material: Material,
+}
+
+impl MeshData {
+    pub fn new(positions: Vec<Vec3>, indices: Vec<[u32; 3]>, mat: Material) -> Self {
+        MeshData { positions, normals: None, uvs: None, colors: None, indices, material: mat }
+    }
+
+    pub fn with_normals(mut self, normals: Vec<Vec3>) -> Self {
+        self.normals = Some(normals);
+        self
+    }
+
+    pub fn with_uvs(mut self, uvs: Vec<(f32, f32)>) -> Self {
+        self.uvs = Some(uvs);
+        self
+    }
+
+    pub fn with_colors(mut self, colors: Vec<Vec3>) -> Self {
+        self.colors = Some(colors);
+        self
+    }
+
+    pub fn num_triangles(&self) -> usize {
+        self.indices.len()
+    }
+
+    pub fn triangle_vertices(&self, index: usize) -> [Vec3; 3] {
+        let [i0, i1, i2] = self.indices[index];
+        [self.positions[i0 as usize], self.positions[i1 as usize], self.positions[i2 as usize]]
+    }
+
+    pub fn triangle_bounding_box(&self, index: usize) -> AABB {
+        let [p0, p1, p2] = self.triangle_vertices(index);
+        triangle_bounding_box(p0, p1, p2)
+    }
+
+    pub fn triangle_hit(&self, index: usize, r: &Ray, t_min: f32, t_max: f32) -> Option<HitRecord<'_>> {
+        let [p0, p1, p2] = self.triangle_vertices(index);
+        let (t, b1, b2) = intersect_triangle(p0, p1, p2, r, t_min, t_max)?;
+        let b0 = 1.0 - b1 - b2;
+        let [i0, i1, i2] = self.indices[index];
+        let (i0, i1, i2) = (i0 as usize, i1 as usize, i2 as usize);
+
+        let normal = match self.normals {
+            Some(ref n) => unit_vector(b0 * n[i0] + b1 * n[i1] + b2 * n[i2]),
+            None => unit_vector(cross(p1 - p0, p2 - p0))
+        };
+
+        let uvs = match self.uvs {
+            Some(ref uv) => [uv[i0], uv[i1], uv[i2]],
+            None => [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)]
+        };
+        let u = b0 * uvs[0].0 + b1 * uvs[1].0 + b2 * uvs[2].0;
+        let v = b0 * uvs[0].1 + b1 * uvs[1].1 + b2 * uvs[2].1;
+        let (dpdu, dpdv) = triangle_uv_derivatives([p0, p1, p2], uvs);
+
+        let color = self.colors.as_ref().map(|c| b0 * c[i0] + b1 * c[i1] + b2 * c[i2]);
+
+        let rec = HitRecord {
+            t,
+            u,
+            v,
+            p: r.point_at_parameter(t),
+            normal,
+            dpdu,
+            dpdv,
+            color,
+            emission: None,
+            material: &self.material };
+
+        Some(rec)
+    }
+}
+
+// A single face of a shared mesh. Cloning one only bumps the reference count on the
+// mesh buffers, so these can be handed to BVHNode::new without duplicating geometry.
+#[derive(Clone)]
+pub struct MeshTriangle {
+    pub mesh: Arc<MeshData>,
+    pub index: usize,
+}
+
+impl Hitable for MeshTriangle {
+    fn hit(&self, r: Ray, t_min: f32, t_max: f32) -> Option<HitRecord<'_>> {
+        self.mesh.triangle_hit(self.index, &r, t_min, t_max)
+    }
+
+    fn bounding_box(&self, _t0: f32, _t1: f32) -> Option<AABB> {
+        Some(self.mesh.triangle_bounding_box(self.index))
+    }
+
+    fn clone_to_box(&self) -> Box<dyn Hitable> {
+        Box::new(self.clone())
+    }
+}
+
+// Flattened BVH node. Leaves reference `count` entries of the triangle order starting
+// at `offset`; interior nodes have their left child immediately after them and the
+// right child at `offset`.
+#[derive(Clone, Copy, Debug)]
+struct MeshBVHNode {
+    aabb: AABB,
+    offset: u32,
+    count: u32,
+}
+
+#[derive(Clone)]
+pub struct TriangleMesh {
+    mesh: Arc<MeshData>,
+    nodes: Arc<Vec<MeshBVHNode>>,
+    triangle_order: Arc<Vec<u32>>,
+}
+
+impl TriangleMesh {
+    pub fn new(data: MeshData) -> Self {
+        let mesh = Arc::new(data);
+        let mut bounds: Vec<(u32, AABB, Vec3)> = (0..mesh.num_triangles())
+            .map(|i| {
+                let aabb = mesh.triangle_bounding_box(i);
+                (i as u32, aabb, 0.5 * (aabb.min() + aabb.max()))
+            })
+            .collect();
+
+        let mut nodes = Vec::new();
+        if !bounds.is_empty() {
+            build_recursive(&mut bounds, 0, &mut nodes);
+        }
+        let triangle_order = bounds.iter().map(|b| b.0).collect();
+
+        TriangleMesh { mesh, nodes: Arc::new(nodes), triangle_order: Arc::new(triangle_order) }
+    }
+
+    pub fn mesh(&self) -> &Arc<MeshData> {
+        &self.mesh
+    }
+
+    pub fn num_triangles(&self) -> usize {
+        self.mesh.num_triangles()
+    }
+
+    pub fn triangles(&self) -> Vec<Box<dyn Hitable>> {
+        (0..self.mesh.num_triangles())
+            .map(|index| Box::new(MeshTriangle { mesh: self.mesh.clone(), index }) as Box<dyn Hitable>)
+            .collect()
+    }
+}
+
+fn build_recursive(bounds: &mut [(u32, AABB, Vec3)], offset: usize, nodes: &mut Vec<MeshBVHNode>) {
+    let mut aabb = bounds[0].1;
+    let mut centroid_min = bounds[0].2;
+    let mut centroid_max = bounds[0].2;
+    for b in bounds.iter().skip(1) {
+        aabb = surrounding_box(&aabb, &b.1);
+        for a in 0..3 {
+            centroid_min[a] = f32::min(centroid_min[a], b.2[a]);
+            centroid_max[a] = f32::max(centroid_max[a], b.2[a]);
+        }
+    }
+
+    let node_index = nodes.len();
+    if bounds.len() <= MAX_TRIANGLES_PER_LEAF {
+        nodes.push(MeshBVHNode { aabb, offset: offset as u32, count: bounds.len() as u32 });
+        return
+    }
+    nodes.push(MeshBVHNode { aabb, offset: 0, count: 0 });
+
+    let extent = centroid_max - centroid_min;
+    let axis = if extent.x() > extent.y() && extent.x() > extent.z() { 0 } else if extent.y() > extent.z() { 1 } else { 2 };
+    let mid = bounds.len() / 2;
+    bounds.sort_unstable_by(|a, b| a.2[axis].partial_cmp(&b.2[axis]).unwrap_or(std::cmp::Ordering::Equal));
+
+    let (left, right) = bounds.split_at_mut(mid);
+    build_recursive(left, offset, nodes);
+    nodes[node_index].offset = nodes.len() as u32;
+    build_recursive(right, offset + mid, nodes);
+}
+
+impl Hitable for TriangleMesh {
+    fn hit(&self, r: Ray, t_min: f32, t_max: f32) -> Option<HitRecord<'_>> {
+        if self.nodes.is_empty() {
+            return None
+        }
+
+        let mut closest_so_far = t_max;
+        let mut best_hit: Option<HitRecord> = None;
+        let mut stack = vec![0usize];
+
+        while let Some(node_index) = stack.pop() {
+            let node = self.nodes[node_index];
+            if !node.aabb.hit(r, t_min, closest_so_far) {
+                continue
+            }
+
+            if node.count > 0 {
+                let start = node.offset as usize;
+                for &tri in &self.triangle_order[start..start + node.count as usize] {
+                    if let Some(hit) = self.mesh.triangle_hit(tri as usize, &r, t_min, closest_so_far) {
+                        closest_so_far = hit.t;
+                        best_hit = Some(hit);
+                    }
+                }
+            }
+            else {
+                stack.push(node.offset as usize);
+                stack.push(node_index + 1);
+            }
+        }
+
+        best_hit
+    }
+
+    fn bounding_box(&self, _t0: f32, _t1: f32) -> Option<AABB> {
+        self.nodes.first().map(|node| node.aabb)
+    }
+
+    fn clone_to_box(&self) -> Box<dyn Hitable> {
+        Box::new(self.clone())
+    }
+}
\ No newline at end of file
@@ -262,4 +262,12 @@ pub fn cross(lhs: Vec3, rhs: Vec3) -> Vec3 {
         lhs.y()*rhs.z() - lhs.z()*rhs.y(), 
         lhs.z()*rhs.x() - lhs.x()*rhs.z(), 
         lhs.x()*rhs.y() - lhs.y()*rhs.x() ] }
+}
+
+// Builds two unit vectors that together with the unit vector n form an orthonormal basis
+pub fn orthonormal_basis(n: Vec3) -> (Vec3, Vec3) {
+    let helper = if n.x().abs() > 0.9 { Vec3::unit_y() } else { Vec3::unit_x() };
+    let tangent = unit_vector(cross(helper, n));
+    let bitangent = cross(n, tangent);
+    (tangent, bitangent)
 }
\ No newline at end of file
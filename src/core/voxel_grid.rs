@@ -0,0 +1,68 @@
+use super::vec3::*;
+use std::sync::Arc;
+
+// A dense 3D grid of scalars with samples at the corners of an nx * ny * nz lattice
+// spanning the unit cube, x varying fastest
+#[derive(Clone, Debug)]
+pub struct VoxelGrid {
+    nx: usize,
+    ny: usize,
+    nz: usize,
+    data: Arc<Vec<f32>>,
+    max_value: f32,
+}
+
+impl VoxelGrid {
+    pub fn new(data: Vec<f32>, nx: usize, ny: usize, nz: usize) -> Self {
+        assert!(nx >= 1 && ny >= 1 && nz >= 1, "a voxel grid needs at least one sample per axis");
+        assert_eq!(data.len(), nx * ny * nz, "voxel count doesn't match the grid dimensions");
+
+        let max_value = data.iter().cloned().fold(0.0, f32::max);
+        VoxelGrid { nx, ny, nz, data: Arc::new(data), max_value }
+    }
+
+    // Fills the grid by evaluating a function at every lattice point in [0, 1]^3
+    pub fn from_fn<F: Fn(Vec3) -> f32>(nx: usize, ny: usize, nz: usize, f: F) -> Self {
+        let coord = |i: usize, n: usize| if n > 1 { i as f32 / (n - 1) as f32 } else { 0.5 };
+        let mut data = Vec::with_capacity(nx * ny * nz);
+        for k in 0..nz {
+            for j in 0..ny {
+                for i in 0..nx {
+                    data.push(f(Vec3::new(coord(i, nx), coord(j, ny), coord(k, nz))));
+                }
+            }
+        }
+        VoxelGrid::new(data, nx, ny, nz)
+    }
+
+    pub fn max_value(&self) -> f32 {
+        self.max_value
+    }
+
+    fn at(&self, i: usize, j: usize, k: usize) -> f32 {
+        self.data[i + self.nx * (j + self.ny * k)]
+    }
+
+    // Trilinear lookup at a point in [0, 1]^3; points outside are clamped to the edge
+    pub fn sample(&self, p: Vec3) -> f32 {
+        let dims = [self.nx, self.ny, self.nz];
+        let mut index = [0usize; 3];
+        let mut frac = [0.0f32; 3];
+        for a in 0..3 {
+            let x = p[a].clamp(0.0, 1.0) * (dims[a] - 1) as f32;
+            index[a] = usize::min(x as usize, dims[a].saturating_sub(2));
+            frac[a] = if dims[a] > 1 { x - index[a] as f32 } else { 0.0 };
+        }
+
+        let next = |a: usize| usize::min(index[a] + 1, dims[a] - 1);
+        let (i0, j0, k0) = (index[0], index[1], index[2]);
+        let (i1, j1, k1) = (next(0), next(1), next(2));
+        let lerp = |a: f32, b: f32, t: f32| a + t * (b - a);
+
+        let c00 = lerp(self.at(i0, j0, k0), self.at(i1, j0, k0), frac[0]);
+        let c10 = lerp(self.at(i0, j1, k0), self.at(i1, j1, k0), frac[0]);
+        let c01 = lerp(self.at(i0, j0, k1), self.at(i1, j0, k1), frac[0]);
+        let c11 = lerp(self.at(i0, j1, k1), self.at(i1, j1, k1), frac[0]);
+        lerp(lerp(c00, c10, frac[1]), lerp(c01, c11, frac[1]), frac[2])
+    }
+}
\ No newline at end of file
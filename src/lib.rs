@@ -1,3 +1,4 @@
 extern crate rand;
 
-pub mod core;
\ No newline at end of file
+pub mod core;
+pub mod loaders;
\ No newline at end of file
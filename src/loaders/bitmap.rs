@@ -0,0 +1,214 @@
+use crate::core::vec3::*;
+use super::LoadError;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+// Decoded pixels as stored in the file, scaled to [0, 1] without any colour space
+// conversion. Rows run top to bottom.
+#[derive(Clone, Debug)]
+pub struct Bitmap {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<Vec3>,
+}
+
+impl Bitmap {
+    pub fn pixel(&self, i: usize, j: usize) -> Vec3 {
+        self.pixels[i + j * self.width]
+    }
+
+    // Rec. 709 luma, used when a colour image stands in for a single channel
+    pub fn luminance(&self, i: usize, j: usize) -> f32 {
+        let p = self.pixel(i, j);
+        0.2126 * p.r() + 0.7152 * p.g() + 0.0722 * p.b()
+    }
+}
+
+pub fn load_bitmap<P: AsRef<Path>>(path: P) -> Result<Bitmap, LoadError> {
+    let file = File::open(path)?;
+    let mut bytes = Vec::new();
+    BufReader::new(file).read_to_end(&mut bytes)?;
+    decode_bitmap(&bytes)
+}
+
+// Picks the decoder from the file's magic number rather than its extension
+pub fn decode_bitmap(bytes: &[u8]) -> Result<Bitmap, LoadError> {
+    if bytes.starts_with(b"\x89PNG") {
+        decode_png(bytes)
+    }
+    else if bytes.starts_with(&[0xff, 0xd8]) {
+        decode_jpeg(bytes)
+    }
+    else if bytes.len() > 2 && bytes[0] == b'P' && (b'2'..=b'6').contains(&bytes[1]) && bytes[1] != b'4' {
+        decode_pnm(bytes)
+    }
+    else {
+        Err(LoadError::Malformed("unrecognized image format".to_string()))
+    }
+}
+
+fn decode_png(bytes: &[u8]) -> Result<Bitmap, LoadError> {
+    let mut decoder = png::Decoder::new(bytes);
+    decoder.set_transformations(png::Transformations::EXPAND);
+    let mut reader = decoder.read_info()
+        .map_err(|err| LoadError::Malformed(format!("invalid PNG: {}", err)))?;
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buffer)
+        .map_err(|err| LoadError::Malformed(format!("invalid PNG: {}", err)))?;
+
+    let channels = match info.color_type {
+        png::ColorType::Grayscale => 1,
+        png::ColorType::GrayscaleAlpha => 2,
+        png::ColorType::Rgb => 3,
+        png::ColorType::Rgba => 4,
+        png::ColorType::Indexed => return Err(LoadError::Malformed("unexpanded palette PNG".to_string())),
+    };
+    let wide = info.bit_depth == png::BitDepth::Sixteen;
+    let sample = |row: &[u8], index: usize| -> f32 {
+        if wide {
+            f32::from(u16::from_be_bytes([row[2 * index], row[2 * index + 1]])) / 65535.0
+        }
+        else {
+            f32::from(row[index]) / 255.0
+        }
+    };
+
+    let (width, height) = (info.width as usize, info.height as usize);
+    let mut pixels = Vec::with_capacity(width * height);
+    for row in buffer.chunks(info.line_size).take(height) {
+        for i in 0..width {
+            let base = i * channels;
+            pixels.push(if channels < 3 {
+                let gray = sample(row, base);
+                Vec3::new(gray, gray, gray)
+            }
+            else {
+                Vec3::new(sample(row, base), sample(row, base + 1), sample(row, base + 2))
+            });
+        }
+    }
+
+    Ok(Bitmap { width, height, pixels })
+}
+
+fn decode_jpeg(bytes: &[u8]) -> Result<Bitmap, LoadError> {
+    let mut decoder = jpeg_decoder::Decoder::new(bytes);
+    let buffer = decoder.decode()
+        .map_err(|err| LoadError::Malformed(format!("invalid JPEG: {}", err)))?;
+    let info = decoder.info()
+        .ok_or_else(|| LoadError::Malformed("JPEG has no frame header".to_string()))?;
+
+    let (width, height) = (usize::from(info.width), usize::from(info.height));
+    let pixels = match info.pixel_format {
+        jpeg_decoder::PixelFormat::L8 => buffer.iter()
+            .map(|&l| {
+                let gray = f32::from(l) / 255.0;
+                Vec3::new(gray, gray, gray)
+            })
+            .collect(),
+        // Lossless 16-bit JPEGs come out in native byte order
+        jpeg_decoder::PixelFormat::L16 => buffer.chunks(2)
+            .map(|l| {
+                let gray = f32::from(u16::from_ne_bytes([l[0], l[1]])) / 65535.0;
+                Vec3::new(gray, gray, gray)
+            })
+            .collect(),
+        jpeg_decoder::PixelFormat::RGB24 => buffer.chunks(3)
+            .map(|c| Vec3::new(f32::from(c[0]), f32::from(c[1]), f32::from(c[2])) / 255.0)
+            .collect(),
+        jpeg_decoder::PixelFormat::CMYK32 => return Err(LoadError::Malformed("CMYK JPEGs are not supported".to_string())),
+    };
+
+    Ok(Bitmap { width, height, pixels })
+}
+
+// Netpbm: P2/P5 greyscale and P3/P6 colour, ASCII or binary, 8 or 16 bits per sample
+fn decode_pnm(bytes: &[u8]) -> Result<Bitmap, LoadError> {
+    let binary = bytes[1] == b'5' || bytes[1] == b'6';
+    let channels = if bytes[1] == b'3' || bytes[1] == b'6' { 3 } else { 1 };
+
+    let mut pos = 2;
+    let mut header = [0usize; 3];
+    for value in header.iter_mut() {
+        *value = next_pnm_number(bytes, &mut pos)?;
+    }
+    let [width, height, max_value] = header;
+    if max_value == 0 || max_value > 65535 {
+        return Err(LoadError::Malformed(format!("invalid PNM maximum value {}", max_value)))
+    }
+
+    // The header values come from the file, so check them against the data actually present
+    // before trusting them
+    let count = width.checked_mul(height).and_then(|pixels| pixels.checked_mul(channels))
+        .filter(|&count| count > 0)
+        .ok_or_else(|| LoadError::Malformed(format!("invalid PNM dimensions {} x {}", width, height)))?;
+
+    let mut samples = Vec::new();
+    if binary {
+        // Exactly one whitespace byte separates the header from the raster
+        pos += 1;
+        let wide = max_value > 255;
+        let size = if wide { 2 } else { 1 };
+        let remaining = bytes.len().saturating_sub(pos);
+        if count.checked_mul(size).map_or(true, |needed| needed > remaining) {
+            return Err(LoadError::Malformed(format!(
+                "truncated PNM raster: {} x {} needs {} samples of {} bytes, {} bytes remain", width, height, count, size, remaining)))
+        }
+        for k in 0..count {
+            let at = pos + k * size;
+            samples.push(if wide { usize::from(u16::from_be_bytes([bytes[at], bytes[at + 1]])) } else { usize::from(bytes[at]) });
+        }
+    }
+    else {
+        // Every sample takes at least one digit, and all but the last a separator
+        let remaining = bytes.len().saturating_sub(pos);
+        if count > remaining / 2 + 1 {
+            return Err(LoadError::Malformed(format!(
+                "truncated PNM raster: {} x {} needs {} samples, only {} bytes remain", width, height, count, remaining)))
+        }
+        for _ in 0..count {
+            samples.push(next_pnm_number(bytes, &mut pos)?);
+        }
+    }
+
+    let scale = 1.0 / max_value as f32;
+    let pixels = samples.chunks(channels)
+        .map(|s| if channels == 1 {
+            let gray = s[0] as f32 * scale;
+            Vec3::new(gray, gray, gray)
+        }
+        else {
+            scale * Vec3::new(s[0] as f32, s[1] as f32, s[2] as f32)
+        })
+        .collect();
+
+    Ok(Bitmap { width, height, pixels })
+}
+
+fn next_pnm_number(bytes: &[u8], pos: &mut usize) -> Result<usize, LoadError> {
+    loop {
+        match bytes.get(*pos) {
+            Some(b'#') => {
+                while *pos < bytes.len() && bytes[*pos] != b'\n' {
+                    *pos += 1;
+                }
+            },
+            Some(c) if c.is_ascii_whitespace() => *pos += 1,
+            _ => break
+        }
+    }
+
+    let start = *pos;
+    while *pos < bytes.len() && bytes[*pos].is_ascii_digit() {
+        *pos += 1;
+    }
+    if start == *pos {
+        return Err(LoadError::Malformed("expected a number in PNM data".to_string()))
+    }
+
+    std::str::from_utf8(&bytes[start..*pos])
+        .ok()
+        .and_then(|s| s.parse::<usize>().ok())
+        .ok_or_else(|| LoadError::Malformed("PNM number out of range".to_string()))
+}
\ No newline at end of file
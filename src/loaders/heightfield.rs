@@ -0,0 +1,24 @@
+use crate::core::vec3::*;
+use crate::core::material::*;
+use crate::core::heightfield::*;
+use super::bitmap::*;
+use super::LoadError;
+use std::path::Path;
+
+// Builds a heightfield from a greyscale image (colour images use their luminance), with
+// image columns along x and rows along z
+pub fn load_heightfield<P: AsRef<Path>>(path: P, origin: Vec3, size: Vec3, mat: Material) -> Result<Heightfield, LoadError> {
+    let bitmap = load_bitmap(path)?;
+    if bitmap.width < 2 || bitmap.height < 2 {
+        return Err(LoadError::Malformed(format!("height map must be at least 2x2, found {}x{}", bitmap.width, bitmap.height)))
+    }
+
+    let mut heights = Vec::with_capacity(bitmap.width * bitmap.height);
+    for j in 0..bitmap.height {
+        for i in 0..bitmap.width {
+            heights.push(bitmap.luminance(i, j));
+        }
+    }
+
+    Ok(Heightfield::new(heights, bitmap.width, bitmap.height, origin, size, mat))
+}
\ No newline at end of file
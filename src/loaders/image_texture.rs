@@ -0,0 +1,19 @@
+use crate::core::vec3::*;
+use crate::core::image_texture::*;
+use super::bitmap::*;
+use super::LoadError;
+use std::path::Path;
+
+// Loads a PNG, JPEG or PNM colour image, treating its texels as sRGB
+pub fn load_image_texture<P: AsRef<Path>>(path: P) -> Result<ImageTexture, LoadError> {
+    let bitmap = load_bitmap(path)?;
+    if bitmap.width == 0 || bitmap.height == 0 {
+        return Err(LoadError::Malformed("image texture has no pixels".to_string()))
+    }
+
+    let texels = bitmap.pixels.iter()
+        .map(|p| Vec3::new(srgb_to_linear(p.r()), srgb_to_linear(p.g()), srgb_to_linear(p.b())))
+        .collect();
+
+    Ok(ImageTexture::new(bitmap.width, bitmap.height, texels))
+}
\ No newline at end of file
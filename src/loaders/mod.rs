@@ -0,0 +1,48 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+pub mod obj;
+pub mod ply;
+pub mod stl;
+pub mod bitmap;
+pub mod heightfield;
+pub mod image_texture;
+
+#[derive(Debug)]
+pub enum LoadError {
+    Io(io::Error),
+    Parse { line: usize, message: String },
+    Malformed(String),
+}
+
+impl LoadError {
+    pub fn parse(line: usize, message: impl Into<String>) -> Self {
+        LoadError::Parse { line, message: message.into() }
+    }
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            LoadError::Io(ref err) => write!(f, "I/O error: {}", err),
+            LoadError::Parse { line, ref message } => write!(f, "line {}: {}", line, message),
+            LoadError::Malformed(ref message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl Error for LoadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            LoadError::Io(ref err) => Some(err),
+            LoadError::Parse { .. } | LoadError::Malformed(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for LoadError {
+    fn from(err: io::Error) -> Self {
+        LoadError::Io(err)
+    }
+}
\ No newline at end of file
@@ -0,0 +1,417 @@
+use crate::core::vec3::*;
+use crate::core::hitable::*;
+use crate::core::hitable_list::*;
+use crate::core::material::*;
+use crate::core::triangle_mesh::*;
+use super::LoadError;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::str::SplitWhitespace;
+
+#[derive(Clone, Debug)]
+pub struct ObjMaterial {
+    pub name: String,
+    pub diffuse: Vec3,
+    pub specular: Vec3,
+    pub emission: Vec3,
+    pub ior: f32,
+    pub dissolve: f32,
+}
+
+impl ObjMaterial {
+    pub fn new(name: &str) -> Self {
+        ObjMaterial {
+            name: name.to_string(),
+            diffuse: Vec3::new(0.8, 0.8, 0.8),
+            specular: Vec3::zero(),
+            emission: Vec3::zero(),
+            ior: 1.0,
+            dissolve: 1.0,
+        }
+    }
+
+    // Emissive materials become lights, translucent ones dielectrics, ones whose specular
+    // colour dominates their diffuse colour metals, and everything else is Lambertian.
+    pub fn to_material(&self) -> Material {
+        if max_component(self.emission) > 0.0 {
+            Material::diffuse_light(self.emission)
+        }
+        else if self.dissolve < 1.0 {
+            let ior = if self.ior > 1.0 { self.ior } else { 1.5 };
+            Material::dielectric(ior)
+        }
+        else if max_component(self.specular) > max_component(self.diffuse) {
+            Material::metal(self.specular)
+        }
+        else {
+            Material::lambertian(self.diffuse)
+        }
+    }
+}
+
+fn max_component(v: Vec3) -> f32 {
+    f32::max(v.x(), f32::max(v.y(), v.z()))
+}
+
+pub struct ObjMesh {
+    pub group: String,
+    pub material_name: Option<String>,
+    pub mesh: TriangleMesh,
+}
+
+pub struct ObjModel {
+    pub meshes: Vec<ObjMesh>,
+    pub materials: HashMap<String, ObjMaterial>,
+}
+
+impl ObjModel {
+    pub fn into_hitables(self) -> Vec<Box<dyn Hitable>> {
+        self.meshes.into_iter().map(|m| Box::new(m.mesh) as Box<dyn Hitable>).collect()
+    }
+
+    pub fn to_hitable_list(&self) -> HitableList {
+        let mut list = HitableList::new();
+        list.list.extend(self.meshes.iter().map(|m| m.mesh.clone_to_box()));
+        list
+    }
+}
+
+pub fn load_obj<P: AsRef<Path>>(path: P) -> Result<ObjModel, LoadError> {
+    let path = path.as_ref();
+    let file = File::open(path)?;
+    parse_obj(BufReader::new(file), path.parent())
+}
+
+pub fn load_mtl<P: AsRef<Path>>(path: P) -> Result<HashMap<String, ObjMaterial>, LoadError> {
+    let file = File::open(path)?;
+    parse_mtl(BufReader::new(file))
+}
+
+// A mesh under construction for one (group, material) pair. OBJ faces index positions,
+// texcoords and normals separately, so each distinct combination becomes one vertex.
+struct MeshBuilder {
+    group: String,
+    material_name: Option<String>,
+    positions: Vec<Vec3>,
+    normals: Vec<Option<Vec3>>,
+    uvs: Vec<Option<(f32, f32)>>,
+    indices: Vec<[u32; 3]>,
+    vertex_map: HashMap<(usize, Option<usize>, Option<usize>), u32>,
+}
+
+impl MeshBuilder {
+    fn new(group: &str, material_name: Option<String>) -> Self {
+        MeshBuilder {
+            group: group.to_string(),
+            material_name,
+            positions: Vec::new(),
+            normals: Vec::new(),
+            uvs: Vec::new(),
+            indices: Vec::new(),
+            vertex_map: HashMap::new(),
+        }
+    }
+
+    fn add_vertex(&mut self, key: (usize, Option<usize>, Option<usize>), state: &ObjState) -> u32 {
+        if let Some(&index) = self.vertex_map.get(&key) {
+            return index
+        }
+
+        let index = self.positions.len() as u32;
+        self.positions.push(state.positions[key.0]);
+        self.uvs.push(key.1.map(|i| state.texcoords[i]));
+        self.normals.push(key.2.map(|i| state.normals[i]));
+        self.vertex_map.insert(key, index);
+        index
+    }
+
+    fn build(self, materials: &HashMap<String, ObjMaterial>) -> ObjMesh {
+        let material = match self.material_name {
+            Some(ref name) => materials.get(name).map(|m| m.to_material()),
+            None => None
+        }.unwrap_or_else(|| ObjMaterial::new("default").to_material());
+
+        let mut data = MeshData::new(self.positions, self.indices, material);
+        if self.normals.iter().all(|n| n.is_some()) {
+            data = data.with_normals(self.normals.into_iter().flatten().collect());
+        }
+        if self.uvs.iter().all(|uv| uv.is_some()) {
+            data = data.with_uvs(self.uvs.into_iter().flatten().collect());
+        }
+
+        ObjMesh { group: self.group, material_name: self.material_name, mesh: TriangleMesh::new(data) }
+    }
+}
+
+struct ObjState {
+    positions: Vec<Vec3>,
+    normals: Vec<Vec3>,
+    texcoords: Vec<(f32, f32)>,
+}
+
+pub fn parse_obj<R: BufRead>(reader: R, mtl_dir: Option<&Path>) -> Result<ObjModel, LoadError> {
+    let mut state = ObjState { positions: Vec::new(), normals: Vec::new(), texcoords: Vec::new() };
+    let mut materials = HashMap::new();
+    let mut builders: Vec<MeshBuilder> = Vec::new();
+    let mut builder_lookup: HashMap<(String, Option<String>), usize> = HashMap::new();
+    let mut group = String::from("default");
+    let mut material_name: Option<String> = None;
+
+    for (line_index, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line_number = line_index + 1;
+        let mut tokens = line.split_whitespace();
+
+        match tokens.next() {
+            Some("v") => {
+                let v = parse_floats(&mut tokens, 3, 3, line_number, "vertex position")?;
+                state.positions.push(Vec3::new(v[0], v[1], v[2]));
+            },
+            Some("vn") => {
+                let v = parse_floats(&mut tokens, 3, 3, line_number, "vertex normal")?;
+                state.normals.push(Vec3::new(v[0], v[1], v[2]));
+            },
+            Some("vt") => {
+                let v = parse_floats(&mut tokens, 1, 2, line_number, "texture coordinate")?;
+                state.texcoords.push((v[0], if v.len() > 1 { v[1] } else { 0.0 }));
+            },
+            Some("f") => {
+                let mut face = Vec::new();
+                for token in tokens {
+                    face.push(parse_face_vertex(token, &state, line_number)?);
+                }
+                if face.len() < 3 {
+                    return Err(LoadError::parse(line_number, format!("face has {} vertices, expected at least 3", face.len())))
+                }
+
+                let key = (group.clone(), material_name.clone());
+                let builder_index = match builder_lookup.get(&key) {
+                    Some(&index) => index,
+                    None => {
+                        builders.push(MeshBuilder::new(&group, material_name.clone()));
+                        builder_lookup.insert(key, builders.len() - 1);
+                        builders.len() - 1
+                    }
+                };
+
+                let builder = &mut builders[builder_index];
+                let indices: Vec<u32> = face.iter().map(|&key| builder.add_vertex(key, &state)).collect();
+                for i in 1..indices.len() - 1 {
+                    builder.indices.push([indices[0], indices[i], indices[i + 1]]);
+                }
+            },
+            Some("g") | Some("o") => {
+                let names: Vec<&str> = tokens.collect();
+                group = if names.is_empty() { String::from("default") } else { names.join(" ") };
+            },
+            Some("usemtl") => {
+                match tokens.next() {
+                    Some(name) => material_name = Some(name.to_string()),
+                    None => return Err(LoadError::parse(line_number, "usemtl is missing a material name"))
+                }
+            },
+            Some("mtllib") => {
+                let dir = mtl_dir.unwrap_or_else(|| Path::new("."));
+                for name in tokens {
+                    let library = load_mtl(dir.join(name)).map_err(|err| {
+                        LoadError::parse(line_number, format!("failed to load material library '{}': {}", name, err))
+                    })?;
+                    materials.extend(library);
+                }
+            },
+            _ => ()
+        }
+    }
+
+    let meshes = builders.into_iter().map(|b| b.build(&materials)).collect();
+    Ok(ObjModel { meshes, materials })
+}
+
+pub fn parse_mtl<R: BufRead>(reader: R) -> Result<HashMap<String, ObjMaterial>, LoadError> {
+    let mut materials = HashMap::new();
+    let mut current: Option<ObjMaterial> = None;
+
+    for (line_index, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line_number = line_index + 1;
+        let mut tokens = line.split_whitespace();
+
+        let keyword = match tokens.next() {
+            Some(keyword) => keyword,
+            None => continue
+        };
+
+        if keyword == "newmtl" {
+            let name = tokens.next().ok_or_else(|| LoadError::parse(line_number, "newmtl is missing a material name"))?;
+            if let Some(material) = current.take() {
+                materials.insert(material.name.clone(), material);
+            }
+            current = Some(ObjMaterial::new(name));
+            continue
+        }
+
+        if !["Kd", "Ks", "Ke", "Ni", "d", "Tr"].contains(&keyword) {
+            continue
+        }
+
+        let material = current.as_mut()
+            .ok_or_else(|| LoadError::parse(line_number, format!("'{}' appears before any newmtl", keyword)))?;
+
+        match keyword {
+            "Kd" => material.diffuse = parse_color(&mut tokens, line_number)?,
+            "Ks" => material.specular = parse_color(&mut tokens, line_number)?,
+            "Ke" => material.emission = parse_color(&mut tokens, line_number)?,
+            "Ni" => material.ior = parse_floats(&mut tokens, 1, 1, line_number, "index of refraction")?[0],
+            "d" => material.dissolve = parse_floats(&mut tokens, 1, 1, line_number, "dissolve")?[0],
+            "Tr" => material.dissolve = 1.0 - parse_floats(&mut tokens, 1, 1, line_number, "transparency")?[0],
+            _ => ()
+        }
+    }
+
+    if let Some(material) = current.take() {
+        materials.insert(material.name.clone(), material);
+    }
+
+    Ok(materials)
+}
+
+fn parse_floats(tokens: &mut SplitWhitespace, min: usize, max: usize, line: usize, what: &str) -> Result<Vec<f32>, LoadError> {
+    let mut values = Vec::with_capacity(max);
+    for token in tokens.take(max) {
+        let value = token.parse::<f32>()
+            .map_err(|_| LoadError::parse(line, format!("invalid number '{}' in {}", token, what)))?;
+        values.push(value);
+    }
+
+    if values.len() < min {
+        return Err(LoadError::parse(line, format!("{} needs {} components, found {}", what, min, values.len())))
+    }
+
+    Ok(values)
+}
+
+fn parse_color(tokens: &mut SplitWhitespace, line: usize) -> Result<Vec3, LoadError> {
+    let c = parse_floats(tokens, 1, 3, line, "colour")?;
+    if c.len() == 3 {
+        Ok(Vec3::new(c[0], c[1], c[2]))
+    }
+    else {
+        Ok(Vec3::new(c[0], c[0], c[0]))
+    }
+}
+
+// Resolves a 1-based (or negative, relative) OBJ index into a 0-based one
+fn resolve_index(token: &str, count: usize, line: usize, what: &str) -> Result<usize, LoadError> {
+    let index = token.parse::<i64>()
+        .map_err(|_| LoadError::parse(line, format!("invalid {} index '{}'", what, token)))?;
+    let resolved = if index < 0 { count as i64 + index } else { index - 1 };
+
+    if index == 0 || resolved < 0 || resolved >= count as i64 {
+        return Err(LoadError::parse(line, format!("{} index {} is out of range ({} defined)", what, index, count)))
+    }
+
+    Ok(resolved as usize)
+}
+
+fn parse_face_vertex(token: &str, state: &ObjState, line: usize) -> Result<(usize, Option<usize>, Option<usize>), LoadError> {
+    let mut parts = token.split('/');
+
+    let position = resolve_index(parts.next().unwrap_or(""), state.positions.len(), line, "vertex")?;
+    let texcoord = match parts.next() {
+        Some("") | None => None,
+        Some(t) => Some(resolve_index(t, state.texcoords.len(), line, "texture coordinate")?)
+    };
+    let normal = match parts.next() {
+        Some("") | None => None,
+        Some(n) => Some(resolve_index(n, state.normals.len(), line, "normal")?)
+    };
+
+    if parts.next().is_some() {
+        return Err(LoadError::parse(line, format!("malformed face vertex '{}'", token)))
+    }
+
+    Ok((position, texcoord, normal))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SQUARE_VERTICES: &str = "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\n";
+
+    fn parse(source: &str) -> Result<ObjModel, LoadError> {
+        parse_obj(source.as_bytes(), None)
+    }
+
+    fn error_line(source: &str) -> usize {
+        match parse(source) {
+            Err(LoadError::Parse { line, .. }) => line,
+            Err(err) => panic!("expected a parse error, got {}", err),
+            Ok(_) => panic!("expected a parse error, but the file loaded"),
+        }
+    }
+
+    fn triangle_counts(model: &ObjModel) -> Vec<usize> {
+        model.meshes.iter().map(|m| m.mesh.num_triangles()).collect()
+    }
+
+    #[test]
+    fn malformed_vertex_lines_report_their_line() {
+        assert_eq!(error_line("v 0 0 0\nv 1 x 0\n"), 2);
+        assert_eq!(error_line("# comment\n\nv 1 2\n"), 3);
+        assert_eq!(error_line("v 0 0 0\nvn 0 1\n"), 2);
+        assert_eq!(error_line("vt\n"), 1);
+    }
+
+    #[test]
+    fn malformed_face_lines_report_their_line() {
+        assert_eq!(error_line(&format!("{}f 1 2\n", SQUARE_VERTICES)), 5);
+        assert_eq!(error_line(&format!("{}f 1 2 a\n", SQUARE_VERTICES)), 5);
+        assert_eq!(error_line(&format!("{}f 1 2 3\nf 1/1/1/1 2 3\n", SQUARE_VERTICES)), 6);
+    }
+
+    #[test]
+    fn out_of_range_indices_are_rejected() {
+        assert_eq!(error_line(&format!("{}f 1 2 5\n", SQUARE_VERTICES)), 5);
+        assert_eq!(error_line(&format!("{}f 0 1 2\n", SQUARE_VERTICES)), 5);
+        assert_eq!(error_line(&format!("{}f 1/1 2/1 3/1\n", SQUARE_VERTICES)), 5);
+        assert_eq!(error_line(&format!("{}vn 0 0 1\nf 1//1 2//2 3//1\n", SQUARE_VERTICES)), 6);
+    }
+
+    #[test]
+    fn negative_indices_are_relative_to_the_end() {
+        let model = parse(&format!("{}f -4 -3 -2\n", SQUARE_VERTICES)).unwrap();
+        assert_eq!(triangle_counts(&model), vec![1]);
+
+        let positions = &model.meshes[0].mesh.mesh().positions;
+        assert_eq!(positions.len(), 3);
+        assert_eq!((positions[2].x(), positions[2].y()), (1.0, 1.0));
+
+        assert_eq!(error_line(&format!("{}f -1 -2 -5\n", SQUARE_VERTICES)), 5);
+    }
+
+    #[test]
+    fn polygons_are_triangulated_as_fans() {
+        let source = format!("{}v 0.5 1.5 0\nf 1 2 3 4\nf 1 2 3 5 4\n", SQUARE_VERTICES);
+        let model = parse(&source).unwrap();
+        assert_eq!(triangle_counts(&model), vec![5]);
+
+        // Vertices shared between the two faces are only stored once
+        let mesh = model.meshes[0].mesh.mesh();
+        assert_eq!(mesh.positions.len(), 5);
+        assert_eq!(mesh.indices[0], [0, 1, 2]);
+        assert_eq!(mesh.indices[1], [0, 2, 3]);
+        assert_eq!(mesh.indices[4], [0, 4, 3]);
+    }
+
+    #[test]
+    fn groups_and_materials_split_meshes() {
+        let source = format!("{}g a\nf 1 2 3\nusemtl red\nf 1 3 4\ng b\nf 1 2 3 4\ng a\nusemtl red\nf 2 3 4\n", SQUARE_VERTICES);
+        let model = parse(&source).unwrap();
+        assert_eq!(triangle_counts(&model), vec![1, 2, 2]);
+        assert_eq!(model.meshes[1].group, "a");
+        assert_eq!(model.meshes[1].material_name.as_deref(), Some("red"));
+    }
+}
\ No newline at end of file
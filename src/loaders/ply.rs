@@ -0,0 +1,466 @@
+use crate::core::vec3::*;
+use crate::core::material::*;
+use crate::core::triangle_mesh::*;
+use super::LoadError;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Format {
+    Ascii,
+    BinaryLittleEndian,
+    BinaryBigEndian,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ScalarType {
+    Int8,
+    UInt8,
+    Int16,
+    UInt16,
+    Int32,
+    UInt32,
+    Float32,
+    Float64,
+}
+
+impl ScalarType {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "char" | "int8" => Some(ScalarType::Int8),
+            "uchar" | "uint8" => Some(ScalarType::UInt8),
+            "short" | "int16" => Some(ScalarType::Int16),
+            "ushort" | "uint16" => Some(ScalarType::UInt16),
+            "int" | "int32" => Some(ScalarType::Int32),
+            "uint" | "uint32" => Some(ScalarType::UInt32),
+            "float" | "float32" => Some(ScalarType::Float32),
+            "double" | "float64" => Some(ScalarType::Float64),
+            _ => None
+        }
+    }
+
+    fn size(self) -> usize {
+        match self {
+            ScalarType::Int8 | ScalarType::UInt8 => 1,
+            ScalarType::Int16 | ScalarType::UInt16 => 2,
+            ScalarType::Int32 | ScalarType::UInt32 | ScalarType::Float32 => 4,
+            ScalarType::Float64 => 8,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+enum PropertyKind {
+    Scalar(ScalarType),
+    List(ScalarType, ScalarType),
+}
+
+#[derive(Clone, Debug)]
+struct Property {
+    name: String,
+    kind: PropertyKind,
+}
+
+#[derive(Clone, Debug)]
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<Property>,
+}
+
+impl Element {
+    fn property_index(&self, names: &[&str]) -> Option<usize> {
+        self.properties.iter().position(|p| names.contains(&p.name.as_str()))
+    }
+}
+
+pub fn load_ply<P: AsRef<Path>>(path: P, mat: Material) -> Result<TriangleMesh, LoadError> {
+    let file = File::open(path)?;
+    parse_ply(BufReader::new(file), mat)
+}
+
+pub fn parse_ply<R: BufRead>(mut reader: R, mat: Material) -> Result<TriangleMesh, LoadError> {
+    let (format, elements, header_lines) = parse_header(&mut reader)?;
+
+    let mut body = match format {
+        Format::Ascii => BodyReader::Ascii { reader: &mut reader, tokens: Vec::new(), line: header_lines },
+        _ => BodyReader::Binary { reader: &mut reader, big_endian: format == Format::BinaryBigEndian },
+    };
+
+    let mut positions = Vec::new();
+    let mut normals = Vec::new();
+    let mut uvs = Vec::new();
+    let mut colors = Vec::new();
+    let mut indices = Vec::new();
+
+    for element in elements.iter() {
+        match element.name.as_str() {
+            "vertex" => read_vertices(&mut body, element, &mut positions, &mut normals, &mut uvs, &mut colors)?,
+            "face" => read_faces(&mut body, element, &mut indices)?,
+            _ => {
+                for _ in 0..element.count {
+                    read_values(&mut body, element)?;
+                }
+            }
+        }
+    }
+
+    for triangle in indices.iter() {
+        if let Some(&index) = triangle.iter().find(|&&i| i as usize >= positions.len()) {
+            return Err(LoadError::Malformed(format!("face references vertex {} but only {} are defined", index, positions.len())))
+        }
+    }
+
+    let mut data = MeshData::new(positions, indices, mat);
+    if !normals.is_empty() {
+        data = data.with_normals(normals);
+    }
+    if !uvs.is_empty() {
+        data = data.with_uvs(uvs);
+    }
+    if !colors.is_empty() {
+        data = data.with_colors(colors);
+    }
+
+    Ok(TriangleMesh::new(data))
+}
+
+fn parse_header<R: BufRead>(reader: &mut R) -> Result<(Format, Vec<Element>, usize), LoadError> {
+    let mut format = None;
+    let mut elements: Vec<Element> = Vec::new();
+    let mut line_number = 0;
+    let mut line = String::new();
+
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Err(LoadError::parse(line_number, "unexpected end of file before end_header"))
+        }
+        line_number += 1;
+
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        if line_number == 1 {
+            if tokens != ["ply"] {
+                return Err(LoadError::parse(line_number, "missing 'ply' magic number"))
+            }
+            continue
+        }
+
+        match tokens.first().cloned() {
+            Some("format") => {
+                format = match tokens.get(1).cloned() {
+                    Some("ascii") => Some(Format::Ascii),
+                    Some("binary_little_endian") => Some(Format::BinaryLittleEndian),
+                    Some("binary_big_endian") => Some(Format::BinaryBigEndian),
+                    _ => return Err(LoadError::parse(line_number, format!("unsupported format '{}'", line.trim())))
+                };
+            },
+            Some("element") => {
+                if tokens.len() != 3 {
+                    return Err(LoadError::parse(line_number, "element needs a name and a count"))
+                }
+                let count = tokens[2].parse::<usize>()
+                    .map_err(|_| LoadError::parse(line_number, format!("invalid element count '{}'", tokens[2])))?;
+                elements.push(Element { name: tokens[1].to_string(), count, properties: Vec::new() });
+            },
+            Some("property") => {
+                let element = elements.last_mut()
+                    .ok_or_else(|| LoadError::parse(line_number, "property declared before any element"))?;
+                let scalar = |name: &str| ScalarType::parse(name)
+                    .ok_or_else(|| LoadError::parse(line_number, format!("unknown property type '{}'", name)));
+
+                let property = match tokens.as_slice() {
+                    ["property", "list", count_type, item_type, name] => Property {
+                        name: name.to_string(),
+                        kind: PropertyKind::List(scalar(count_type)?, scalar(item_type)?)
+                    },
+                    ["property", value_type, name] => Property {
+                        name: name.to_string(),
+                        kind: PropertyKind::Scalar(scalar(value_type)?)
+                    },
+                    _ => return Err(LoadError::parse(line_number, "malformed property declaration"))
+                };
+                element.properties.push(property);
+            },
+            Some("end_header") => break,
+            Some("comment") | Some("obj_info") | None => (),
+            Some(keyword) => return Err(LoadError::parse(line_number, format!("unexpected header keyword '{}'", keyword)))
+        }
+    }
+
+    match format {
+        Some(format) => Ok((format, elements, line_number)),
+        None => Err(LoadError::parse(line_number, "header has no format line"))
+    }
+}
+
+enum BodyReader<'a, R: BufRead> {
+    Ascii { reader: &'a mut R, tokens: Vec<String>, line: usize },
+    Binary { reader: &'a mut R, big_endian: bool },
+}
+
+impl<'a, R: BufRead> BodyReader<'a, R> {
+    fn error(&self, message: String) -> LoadError {
+        match *self {
+            BodyReader::Ascii { line, .. } => LoadError::parse(line, message),
+            BodyReader::Binary { .. } => LoadError::Malformed(message),
+        }
+    }
+
+    fn read_scalar(&mut self, ty: ScalarType) -> Result<f64, LoadError> {
+        match *self {
+            BodyReader::Ascii { ref mut reader, ref mut tokens, ref mut line } => {
+                while tokens.is_empty() {
+                    let mut text = String::new();
+                    if reader.read_line(&mut text)? == 0 {
+                        return Err(LoadError::parse(*line, "unexpected end of file"))
+                    }
+                    *line += 1;
+                    tokens.extend(text.split_whitespace().rev().map(|t| t.to_string()));
+                }
+
+                let token = tokens.pop().unwrap_or_default();
+                token.parse::<f64>().map_err(|_| LoadError::parse(*line, format!("invalid number '{}'", token)))
+            },
+            BodyReader::Binary { ref mut reader, big_endian } => {
+                let mut bytes = [0u8; 8];
+                let size = ty.size();
+                reader.read_exact(&mut bytes[..size])
+                    .map_err(|_| LoadError::Malformed("unexpected end of binary data".to_string()))?;
+                if big_endian {
+                    bytes[..size].reverse();
+                }
+
+                let value = match ty {
+                    ScalarType::Int8 => f64::from(bytes[0] as i8),
+                    ScalarType::UInt8 => f64::from(bytes[0]),
+                    ScalarType::Int16 => f64::from(i16::from_le_bytes([bytes[0], bytes[1]])),
+                    ScalarType::UInt16 => f64::from(u16::from_le_bytes([bytes[0], bytes[1]])),
+                    ScalarType::Int32 => f64::from(i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])),
+                    ScalarType::UInt32 => f64::from(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])),
+                    ScalarType::Float32 => f64::from(f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])),
+                    ScalarType::Float64 => f64::from_le_bytes(bytes),
+                };
+                Ok(value)
+            }
+        }
+    }
+}
+
+// Reads one element instance, returning the values of each of its properties
+fn read_values<R: BufRead>(body: &mut BodyReader<R>, element: &Element) -> Result<Vec<Vec<f64>>, LoadError> {
+    let mut values = Vec::with_capacity(element.properties.len());
+
+    for property in element.properties.iter() {
+        match property.kind {
+            PropertyKind::Scalar(ty) => values.push(vec![body.read_scalar(ty)?]),
+            PropertyKind::List(count_type, item_type) => {
+                let count = body.read_scalar(count_type)?;
+                if count < 0.0 || count.fract() != 0.0 {
+                    return Err(body.error(format!("invalid list length {} in '{}'", count, property.name)))
+                }
+
+                // The length comes from the file, so grow the list as items are actually read
+                let mut items = Vec::new();
+                for _ in 0..count as u64 {
+                    items.push(body.read_scalar(item_type)?);
+                }
+                values.push(items);
+            }
+        }
+    }
+
+    Ok(values)
+}
+
+// Colours stored as unsigned integers span the type's range, floating point ones are
+// already normalized. Signed integer colours have no agreed meaning and are rejected.
+fn color_scale(element: &Element, index: usize) -> Option<f64> {
+    match element.properties[index].kind {
+        PropertyKind::Scalar(ScalarType::UInt8) => Some(1.0 / f64::from(u8::MAX)),
+        PropertyKind::Scalar(ScalarType::UInt16) => Some(1.0 / f64::from(u16::MAX)),
+        PropertyKind::Scalar(ScalarType::UInt32) => Some(1.0 / f64::from(u32::MAX)),
+        PropertyKind::Scalar(ScalarType::Float32) | PropertyKind::Scalar(ScalarType::Float64) => Some(1.0),
+        _ => None
+    }
+}
+
+fn read_vertices<R: BufRead>(
+    body: &mut BodyReader<R>,
+    element: &Element,
+    positions: &mut Vec<Vec3>,
+    normals: &mut Vec<Vec3>,
+    uvs: &mut Vec<(f32, f32)>,
+    colors: &mut Vec<Vec3>) -> Result<(), LoadError> {
+
+    let position = [element.property_index(&["x"]), element.property_index(&["y"]), element.property_index(&["z"])];
+    let normal = [element.property_index(&["nx"]), element.property_index(&["ny"]), element.property_index(&["nz"])];
+    let uv = [element.property_index(&["u", "s", "texture_u", "texture_s"]), element.property_index(&["v", "t", "texture_v", "texture_t"])];
+    let color = [element.property_index(&["red"]), element.property_index(&["green"]), element.property_index(&["blue"])];
+
+    let [px, py, pz] = match position {
+        [Some(x), Some(y), Some(z)] => [x, y, z],
+        _ => return Err(body.error("vertex element is missing x, y or z".to_string()))
+    };
+
+    let color_scales = match color {
+        [Some(r), Some(g), Some(b)] => {
+            let scales = [color_scale(element, r), color_scale(element, g), color_scale(element, b)];
+            match scales {
+                [Some(r), Some(g), Some(b)] => Some([r as f32, g as f32, b as f32]),
+                _ => return Err(body.error("vertex colours must be unsigned integers or floats".to_string()))
+            }
+        },
+        _ => None
+    };
+
+    for _ in 0..element.count {
+        let values = read_values(body, element)?;
+        let value = |i: usize| values[i].first().cloned().unwrap_or(0.0) as f32;
+
+        positions.push(Vec3::new(value(px), value(py), value(pz)));
+        if let [Some(nx), Some(ny), Some(nz)] = normal {
+            normals.push(Vec3::new(value(nx), value(ny), value(nz)));
+        }
+        if let [Some(u), Some(v)] = uv {
+            uvs.push((value(u), value(v)));
+        }
+        if let ([Some(r), Some(g), Some(b)], Some(scale)) = (color, color_scales) {
+            colors.push(Vec3::new(scale[0] * value(r), scale[1] * value(g), scale[2] * value(b)));
+        }
+    }
+
+    Ok(())
+}
+
+fn read_faces<R: BufRead>(body: &mut BodyReader<R>, element: &Element, indices: &mut Vec<[u32; 3]>) -> Result<(), LoadError> {
+    let list = element.property_index(&["vertex_indices", "vertex_index"])
+        .ok_or_else(|| body.error("face element has no vertex_indices list".to_string()))?;
+
+    for _ in 0..element.count {
+        let values = read_values(body, element)?;
+        let face = &values[list];
+        if face.len() < 3 {
+            return Err(body.error(format!("face has {} vertices, expected at least 3", face.len())))
+        }
+
+        let mut face_indices = Vec::with_capacity(face.len());
+        for &value in face.iter() {
+            face_indices.push(vertex_index(body, value)?);
+        }
+        for i in 1..face_indices.len() - 1 {
+            indices.push([face_indices[0], face_indices[i], face_indices[i + 1]]);
+        }
+    }
+
+    Ok(())
+}
+
+fn vertex_index<R: BufRead>(body: &BodyReader<R>, value: f64) -> Result<u32, LoadError> {
+    if value < 0.0 || value.fract() != 0.0 || value > f64::from(u32::MAX) {
+        return Err(body.error(format!("invalid vertex index {} in face", value)))
+    }
+    Ok(value as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const QUAD_POSITIONS: [[f64; 3]; 4] = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]];
+
+    fn quad_header(format: &str) -> String {
+        format!("ply\nformat {} 1.0\nelement vertex 4\nproperty double x\nproperty float y\nproperty float z\n\
+                 element face 1\nproperty list uchar int vertex_indices\nend_header\n", format)
+    }
+
+    fn ascii_quad(face: &str) -> String {
+        format!("{}0 0 0\n1 0 0\n1 1 0\n0 1 0\n{}\n", quad_header("ascii"), face)
+    }
+
+    fn binary_quad(big_endian: bool) -> Vec<u8> {
+        let format = if big_endian { "binary_big_endian" } else { "binary_little_endian" };
+        let mut bytes = quad_header(format).into_bytes();
+        let mut push = |le: &[u8], be: &[u8]| bytes.extend_from_slice(if big_endian { be } else { le });
+
+        for p in QUAD_POSITIONS.iter() {
+            push(&p[0].to_le_bytes(), &p[0].to_be_bytes());
+            push(&(p[1] as f32).to_le_bytes(), &(p[1] as f32).to_be_bytes());
+            push(&(p[2] as f32).to_le_bytes(), &(p[2] as f32).to_be_bytes());
+        }
+        push(&[4], &[4]);
+        for i in 0..4i32 {
+            push(&i.to_le_bytes(), &i.to_be_bytes());
+        }
+        bytes
+    }
+
+    fn parse(bytes: &[u8]) -> Result<TriangleMesh, LoadError> {
+        parse_ply(bytes, Material::lambertian(Vec3::new(0.5, 0.5, 0.5)))
+    }
+
+    fn assert_quad(mesh: &TriangleMesh) {
+        let data = mesh.mesh();
+        assert_eq!(data.indices, vec![[0, 1, 2], [0, 2, 3]]);
+        for (p, expected) in data.positions.iter().zip(QUAD_POSITIONS.iter()) {
+            assert_eq!([p.x(), p.y(), p.z()], [expected[0] as f32, expected[1] as f32, expected[2] as f32]);
+        }
+    }
+
+    fn color_triangle(ty: &str, color: &str) -> String {
+        format!("ply\nformat ascii 1.0\nelement vertex 3\nproperty float x\nproperty float y\nproperty float z\n\
+                 property {0} red\nproperty {0} green\nproperty {0} blue\nelement face 1\n\
+                 property list uchar int vertex_indices\nend_header\n\
+                 0 0 0 {1}\n1 0 0 {1}\n0 1 0 {1}\n3 0 1 2\n", ty, color)
+    }
+
+    #[test]
+    fn ascii_quad_is_triangulated_as_a_fan() {
+        assert_quad(&parse(ascii_quad("4 0 1 2 3").as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn binary_quad_in_either_byte_order() {
+        assert_quad(&parse(&binary_quad(false)).unwrap());
+        assert_quad(&parse(&binary_quad(true)).unwrap());
+    }
+
+    #[test]
+    fn invalid_list_lengths_are_rejected() {
+        for face in ["-1 0 1 2", "2.5 0 1 2"].iter() {
+            match parse(ascii_quad(face).as_bytes()) {
+                Err(LoadError::Parse { line, .. }) => assert_eq!(line, 14),
+                Err(err) => panic!("unexpected error: {}", err),
+                Ok(_) => panic!("'{}' loaded", face),
+            }
+        }
+    }
+
+    #[test]
+    fn out_of_range_face_index_is_malformed() {
+        match parse(ascii_quad("4 0 1 2 7").as_bytes()) {
+            Err(LoadError::Malformed(message)) => assert!(message.contains("vertex 7"), "{}", message),
+            Err(err) => panic!("unexpected error: {}", err),
+            Ok(_) => panic!("out of range index loaded"),
+        }
+    }
+
+    #[test]
+    fn truncated_binary_body_is_malformed() {
+        let mut bytes = binary_quad(false);
+        bytes.truncate(bytes.len() - 5);
+        assert!(matches!(parse(&bytes), Err(LoadError::Malformed(_))));
+    }
+
+    #[test]
+    fn colours_are_scaled_by_their_type() {
+        for &(ty, color) in [("uchar", "255 51 0"), ("ushort", "65535 13107 0"), ("float", "1 0.2 0")].iter() {
+            let mesh = parse(color_triangle(ty, color).as_bytes()).unwrap();
+            let c = mesh.mesh().colors.as_ref().unwrap()[1];
+            assert!((c.x() - 1.0).abs() < 1e-6 && (c.y() - 0.2).abs() < 1e-6 && c.z() == 0.0, "{}: {:?}", ty, c);
+        }
+
+        assert!(parse(color_triangle("char", "127 20 0").as_bytes()).is_err());
+    }
+}
\ No newline at end of file
@@ -0,0 +1,229 @@
+use crate::core::vec3::*;
+use crate::core::material::*;
+use crate::core::triangle_mesh::*;
+use super::LoadError;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+const BINARY_HEADER_SIZE: usize = 84;
+const BINARY_FACET_SIZE: usize = 50;
+
+pub fn load_stl<P: AsRef<Path>>(path: P, mat: Material) -> Result<TriangleMesh, LoadError> {
+    let file = File::open(path)?;
+    parse_stl(BufReader::new(file), mat)
+}
+
+// Facets don't share vertices in STL, so every corner gets its own vertex carrying
+// the facet normal.
+pub fn parse_stl<R: Read>(mut reader: R, mat: Material) -> Result<TriangleMesh, LoadError> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+
+    let mut positions = Vec::new();
+    let mut normals = Vec::new();
+
+    if is_binary(&bytes) {
+        parse_binary(&bytes, &mut positions, &mut normals)?;
+    }
+    else {
+        let text = std::str::from_utf8(&bytes)
+            .map_err(|_| LoadError::Malformed("ASCII STL is not valid UTF-8".to_string()))?;
+        parse_ascii(text, &mut positions, &mut normals)?;
+    }
+
+    let indices = (0..positions.len() as u32 / 3).map(|i| [3 * i, 3 * i + 1, 3 * i + 2]).collect();
+    let data = MeshData::new(positions, indices, mat).with_normals(normals);
+
+    Ok(TriangleMesh::new(data))
+}
+
+// Binary files whose size matches their triangle count are binary even if their header
+// starts with "solid". Otherwise only text that says "solid" is read as ASCII, so that
+// truncated binary files are reported as such rather than as ASCII syntax errors.
+fn is_binary(bytes: &[u8]) -> bool {
+    if bytes.len() >= BINARY_HEADER_SIZE && Some(bytes.len()) == binary_size(bytes) {
+        return true
+    }
+
+    let text = match std::str::from_utf8(bytes) {
+        Ok(text) if !text.contains('\0') => text,
+        _ => return true
+    };
+    !text.trim_start().starts_with("solid")
+}
+
+// The size a binary file should have according to its triangle count
+fn binary_size(bytes: &[u8]) -> Option<usize> {
+    let count = u32::from_le_bytes([bytes[80], bytes[81], bytes[82], bytes[83]]) as usize;
+    count.checked_mul(BINARY_FACET_SIZE).and_then(|size| size.checked_add(BINARY_HEADER_SIZE))
+}
+
+// Uses the stored facet normal unless the exporter left it zeroed
+fn facet_normal(stored: Vec3, p0: Vec3, p1: Vec3, p2: Vec3) -> Vec3 {
+    if stored.squared_length() > 0.0 {
+        unit_vector(stored)
+    }
+    else {
+        unit_vector(cross(p1 - p0, p2 - p0))
+    }
+}
+
+fn read_vec3(bytes: &[u8]) -> Vec3 {
+    let f = |i: usize| f32::from_le_bytes([bytes[i], bytes[i + 1], bytes[i + 2], bytes[i + 3]]);
+    Vec3::new(f(0), f(4), f(8))
+}
+
+fn parse_binary(bytes: &[u8], positions: &mut Vec<Vec3>, normals: &mut Vec<Vec3>) -> Result<(), LoadError> {
+    if bytes.len() < BINARY_HEADER_SIZE {
+        return Err(LoadError::Malformed(format!("binary STL is {} bytes, shorter than its header", bytes.len())))
+    }
+
+    let count = u32::from_le_bytes([bytes[80], bytes[81], bytes[82], bytes[83]]);
+    match binary_size(bytes) {
+        Some(size) if size <= bytes.len() => (),
+        _ => return Err(LoadError::Malformed(format!(
+            "binary STL declares {} triangles but only has {} bytes of facet data", count, bytes.len() - BINARY_HEADER_SIZE)))
+    }
+
+    for facet in bytes[BINARY_HEADER_SIZE..].chunks_exact(BINARY_FACET_SIZE).take(count as usize) {
+
+        let p0 = read_vec3(&facet[12..24]);
+        let p1 = read_vec3(&facet[24..36]);
+        let p2 = read_vec3(&facet[36..48]);
+        let normal = facet_normal(read_vec3(&facet[0..12]), p0, p1, p2);
+
+        positions.extend_from_slice(&[p0, p1, p2]);
+        normals.extend_from_slice(&[normal, normal, normal]);
+    }
+
+    Ok(())
+}
+
+fn parse_ascii(text: &str, positions: &mut Vec<Vec3>, normals: &mut Vec<Vec3>) -> Result<(), LoadError> {
+    let mut normal: Option<Vec3> = None;
+    let mut vertices: Vec<Vec3> = Vec::with_capacity(3);
+
+    for (line_index, line) in text.lines().enumerate() {
+        let line_number = line_index + 1;
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+
+        match tokens.first().cloned() {
+            Some("facet") => {
+                if normal.is_some() {
+                    return Err(LoadError::parse(line_number, "facet started before the previous endfacet"))
+                }
+                if tokens.get(1) != Some(&"normal") {
+                    return Err(LoadError::parse(line_number, "expected 'facet normal nx ny nz'"))
+                }
+                normal = Some(parse_vec3(&tokens[2..], line_number, "facet normal")?);
+                vertices.clear();
+            },
+            Some("vertex") => {
+                if normal.is_none() {
+                    return Err(LoadError::parse(line_number, "vertex outside of a facet"))
+                }
+                vertices.push(parse_vec3(&tokens[1..], line_number, "vertex")?);
+            },
+            Some("endfacet") => {
+                let stored = normal.take().ok_or_else(|| LoadError::parse(line_number, "endfacet without a matching facet"))?;
+                if vertices.len() != 3 {
+                    return Err(LoadError::parse(line_number, format!("facet has {} vertices, expected 3", vertices.len())))
+                }
+
+                let n = facet_normal(stored, vertices[0], vertices[1], vertices[2]);
+                positions.extend_from_slice(&vertices);
+                normals.extend_from_slice(&[n, n, n]);
+            },
+            Some("solid") | Some("endsolid") | Some("outer") | Some("endloop") | None => (),
+            Some(keyword) => return Err(LoadError::parse(line_number, format!("unexpected keyword '{}'", keyword)))
+        }
+    }
+
+    if normal.is_some() {
+        return Err(LoadError::Malformed("unexpected end of file inside a facet".to_string()))
+    }
+
+    Ok(())
+}
+
+fn parse_vec3(tokens: &[&str], line: usize, what: &str) -> Result<Vec3, LoadError> {
+    if tokens.len() != 3 {
+        return Err(LoadError::parse(line, format!("{} needs 3 components, found {}", what, tokens.len())))
+    }
+
+    let mut v = Vec3::zero();
+    for (i, token) in tokens.iter().enumerate() {
+        v[i] = token.parse::<f32>()
+            .map_err(|_| LoadError::parse(line, format!("invalid number '{}' in {}", token, what)))?;
+    }
+
+    Ok(v)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn binary_stl(header: &str, count: u32, facets: &[[Vec3; 4]]) -> Vec<u8> {
+        let mut bytes = header.as_bytes().to_vec();
+        bytes.resize(80, 0);
+        bytes.extend_from_slice(&count.to_le_bytes());
+        for facet in facets.iter() {
+            for v in facet.iter() {
+                for i in 0..3 {
+                    bytes.extend_from_slice(&v[i].to_le_bytes());
+                }
+            }
+            bytes.extend_from_slice(&[0, 0]);
+        }
+        bytes
+    }
+
+    fn facet(offset: f32) -> [Vec3; 4] {
+        [Vec3::new(0.0, 0.0, 1.0), Vec3::new(offset, 0.0, 0.0), Vec3::new(offset + 1.0, 0.0, 0.0), Vec3::new(offset, 1.0, 0.0)]
+    }
+
+    fn parse(bytes: &[u8]) -> Result<TriangleMesh, LoadError> {
+        parse_stl(bytes, Material::lambertian(Vec3::new(0.5, 0.5, 0.5)))
+    }
+
+    #[test]
+    fn binary_file_with_solid_header_is_read_as_binary() {
+        let bytes = binary_stl("solid exported_part", 2, &[facet(0.0), facet(2.0)]);
+        let mesh = parse(&bytes).unwrap();
+        assert_eq!(mesh.num_triangles(), 2);
+
+        let positions = &mesh.mesh().positions;
+        assert_eq!(positions.len(), 6);
+        assert_eq!((positions[4].x(), positions[4].y()), (3.0, 0.0));
+    }
+
+    #[test]
+    fn truncated_binary_triangle_count_is_an_error() {
+        for header in ["binary part", "solid exported_part"].iter() {
+            let bytes = binary_stl(header, 3, &[facet(0.0), facet(2.0)]);
+            match parse(&bytes) {
+                Err(LoadError::Malformed(message)) => assert!(message.contains("declares 3 triangles"), "{}", message),
+                Err(err) => panic!("unexpected error: {}", err),
+                Ok(_) => panic!("truncated file loaded"),
+            }
+        }
+
+        let mut bytes = binary_stl("binary part", 1, &[facet(0.0)]);
+        bytes.truncate(60);
+        assert!(parse(&bytes).is_err());
+    }
+
+    #[test]
+    fn ascii_file_is_read() {
+        let text = "solid part\nfacet normal 0 0 1\nouter loop\nvertex 0 0 0\nvertex 1 0 0\nvertex 0 1 0\nendloop\nendfacet\nendsolid part\n";
+        assert_eq!(parse(text.as_bytes()).unwrap().num_triangles(), 1);
+
+        match parse(b"solid part\nfacet normal 0 0 1\nvertex 0 0\n") {
+            Err(LoadError::Parse { line, .. }) => assert_eq!(line, 3),
+            _ => panic!("expected a parse error")
+        }
+    }
+}
\ No newline at end of file